@@ -2,22 +2,48 @@
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
+pub mod runtime_api;
+pub mod traits;
 pub mod weights;
 pub use weights::*;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
+	use crate::traits::NameChangeHandler;
+	use codec::DecodeAll;
 	use frame_support::{
+		dispatch::PostDispatchInfo,
 		pallet_prelude::{OptionQuery, *},
-		Blake2_128Concat,
+		sp_runtime::{
+			traits::{Hash, Saturating, StaticLookup},
+			SaturatedConversion,
+		},
+		storage::StoragePrefixedMap,
+		Blake2_128Concat, CloneNoBound, DebugNoBound, EqNoBound, PartialEqNoBound,
 	};
 	use frame_system::{ensure_signed, pallet_prelude::*};
-	use scale_info::{prelude::vec::Vec, TypeInfo};
+	use scale_info::{
+		prelude::{collections::BTreeMap, vec::Vec},
+		TypeInfo,
+	};
+
+	type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -26,35 +52,478 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 		#[pallet::constant]
 		type MaxLength: Get<u32>;
+		/// The longest title a profile may hold. Must be at least `MaxLength`.
+		#[pallet::constant]
+		type MaxTitleLength: Get<u32>;
+		/// The origin which may forcibly maintain profiles of other accounts.
+		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The title given to users who register without one. Must fit within `MaxTitleLength`.
+		type DefaultTitle: Get<&'static [u8]>;
+		/// Users younger than this are considered minors.
+		#[pallet::constant]
+		type AdultAge: Get<u8>;
+		/// Titles that minors may not hold.
+		type AdultOnlyTitles: Get<Vec<&'static [u8]>>;
+		/// How many blocks must pass between `commit_name` and `reveal_name`.
+		#[pallet::constant]
+		type MinCommitDelay: Get<BlockNumberFor<Self>>;
+		/// How many blocks an `offer_transfer` offer stays open for.
+		#[pallet::constant]
+		type TransferOfferTimeout: Get<BlockNumberFor<Self>>;
+		/// The most accounts a single batched read or call handles.
+		#[pallet::constant]
+		type MaxBatch: Get<u32>;
+		/// Whether names shaped like an account address, in hex or SS58, are refused.
+		type RejectAddressLikeNames: Get<bool>;
+		/// Whether a profile may repeat another account's exact name, age and title.
+		type RejectDuplicateProfiles: Get<bool>;
+		/// Whether `insert_user_info` ignores the given title and uses the one of the caller's
+		/// bracket in [`AgeTitleBrackets`].
+		type AutoTitleFromAge: Get<bool>;
+		/// The most aliases a single account may point at its profile.
+		#[pallet::constant]
+		type MaxRedirects: Get<u32>;
+		/// The most profiles signed calls may register in one block.
+		#[pallet::constant]
+		type MaxRegistrationsPerBlock: Get<u32>;
+		/// The most claims one profile may carry.
+		#[pallet::constant]
+		type MaxClaims: Get<u32>;
+		/// The longest value a claim may have.
+		#[pallet::constant]
+		type ClaimLen: Get<u32>;
+		/// How many blocks must pass between two `heartbeat` calls by the same account.
+		#[pallet::constant]
+		type HeartbeatInterval: Get<BlockNumberFor<Self>>;
+		/// The most weight a single bounded admin call may spend on its items, whatever its
+		/// `limit`.
+		#[pallet::constant]
+		type AdminWeightBudget: Get<Weight>;
+		/// Told about every change to a stored profile's name, whoever makes it.
+		type OnNameChange: NameChangeHandler<Self::AccountId>;
 	}
 
-	#[derive(Debug, Encode, Decode, Default, MaxEncodedLen, TypeInfo)]
+	#[derive(
+		CloneNoBound,
+		DebugNoBound,
+		EqNoBound,
+		PartialEqNoBound,
+		Encode,
+		Decode,
+		MaxEncodedLen,
+		TypeInfo,
+	)]
 	#[scale_info(skip_type_params(T))]
 	pub struct User<T: Config> {
 		pub name: BoundedVec<u8, T::MaxLength>,
 		pub age: u8,
-		pub title: BoundedVec<u8, T::MaxLength>,
+		pub title: BoundedVec<u8, T::MaxTitleLength>,
+		/// Set by the force origin to mark the profile as disputed.
+		pub flagged: bool,
+		pub flag_reason: BoundedVec<u8, T::MaxLength>,
+		/// Set by the owner to refuse their own edits until unlocked.
+		pub locked: bool,
+		/// Set by the owner to keep their age out of runtime API reads. The stored age is
+		/// still used for title checks.
+		pub age_hidden: bool,
+		pub pronoun: Option<Pronoun>,
+		/// Set by the owner to keep the whole profile out of runtime API reads by others.
+		pub visibility: Visibility,
+		/// Cleared by the owner to stay out of enumeration and search reads. Lookups of the
+		/// account itself are unaffected.
+		pub discoverable: bool,
+	}
+
+	/// An empty profile. Profiles are discoverable unless their owner opts out.
+	impl<T: Config> Default for User<T> {
+		fn default() -> Self {
+			Self {
+				name: Default::default(),
+				age: Default::default(),
+				title: Default::default(),
+				flagged: false,
+				flag_reason: Default::default(),
+				locked: false,
+				age_hidden: false,
+				pronoun: None,
+				visibility: Default::default(),
+				discoverable: true,
+			}
+		}
+	}
+
+	/// Who may read a profile through the runtime API.
+	#[derive(
+		Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode, MaxEncodedLen, TypeInfo,
+	)]
+	pub enum Visibility {
+		#[default]
+		Public,
+		Private,
+	}
+
+	/// The pronouns a profile may list.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+	pub enum Pronoun {
+		SheHer,
+		HeHim,
+		TheyThem,
+		AskMe,
+	}
+
+	/// A set of optional changes to apply to an existing [`User`]. Fields left as `None` are
+	/// kept as they are.
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Default, TypeInfo)]
+	pub struct ProfileUpdate {
+		pub name: Option<Vec<u8>>,
+		pub age: Option<u8>,
+		pub title: Option<Vec<u8>>,
+	}
+
+	/// The argument of `edit`: a [`ProfileUpdate`] and whether to stop after validating it.
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Default, TypeInfo)]
+	pub struct EditRequest {
+		pub changes: ProfileUpdate,
+		/// Check the changes as `update_profile` would, without storing them.
+		pub validate_only: bool,
+	}
+
+	/// The kinds of claim a profile may carry, each with its own value format.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+	pub enum ClaimType {
+		/// `local@domain.tld`.
+		Email,
+		/// An `https://` URL.
+		Website,
+	}
+
+	/// A profile's state as a single value, for clients that would otherwise combine its flags.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub enum ProfileStatus {
+		Active,
+		/// Flagged by the force origin. Takes precedence over `Locked`.
+		Flagged,
+		/// Locked by its owner.
+		Locked,
+	}
+
+	/// How far an unfinished `force_rebuild_indexes` run has got.
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+	pub enum RebuildStage<AccountId> {
+		/// Old [`LengthHistogram`] and [`ProfileHashes`] entries are still being removed.
+		Clearing,
+		/// Profiles are being counted, after the given account if any.
+		Counting(Option<AccountId>),
+	}
+
+	/// Pallet-wide counters, as returned by the runtime API.
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub struct NickStats {
+		pub user_count: u32,
+	}
+
+	/// Limits the force origin may tighten at runtime without an upgrade. The
+	/// `#[pallet::constant]`s they shadow stay the hard caps.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+	pub struct RuntimeConfig {
+		/// The shortest name or alias accepted.
+		pub min_length: u32,
+		/// The longest name or alias accepted, at most `MaxLength`.
+		pub max_length: u32,
+	}
+
+	/// The pallet's configured limits, as returned by the runtime API.
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub struct NickConfig {
+		pub max_length: u32,
+		pub max_title_length: u32,
+		pub adult_age: u8,
+		pub min_commit_delay: u32,
+		pub transfer_offer_timeout: u32,
+		pub max_redirects: u32,
+		pub max_registrations_per_block: u32,
+		pub max_claims: u32,
+		pub claim_len: u32,
+		pub heartbeat_interval: u32,
+		pub admin_weight_budget: Weight,
+		pub max_batch: u32,
 	}
 
 	#[pallet::storage]
 	pub type AccountToUserInfo<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::AccountId, User<T>, OptionQuery>;
 
+	/// How many stored names have each byte length. Empty buckets are removed.
+	#[pallet::storage]
+	pub type LengthHistogram<T: Config> = StorageMap<_, Twox64Concat, u32, u32, ValueQuery>;
+
+	/// How many `UserInfoAdded` and `UserInfoUpdated` events each account has had, so clients
+	/// can spot a missed one by a gap in the numbers. It belongs to the account rather than the
+	/// profile and is never reset, so it carries on if the profile is merged or transferred away
+	/// and the account registers again.
+	#[pallet::storage]
+	pub type Seq<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Claims the owner attached to their profile, at most one per [`ClaimType`].
+	///
+	/// They are kept apart from [`User`] so that adding them did not mean re-encoding every
+	/// stored profile.
+	#[pallet::storage]
+	pub type Claims<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(ClaimType, BoundedVec<u8, T::ClaimLen>), T::MaxClaims>,
+		ValueQuery,
+	>;
+
+	/// Overrides for the constants, set by `set_runtime_config`.
+	#[pallet::storage]
+	pub type Overrides<T: Config> = StorageValue<_, RuntimeConfig, OptionQuery>;
+
+	/// The block of each account's latest `heartbeat`.
+	#[pallet::storage]
+	pub type LastSeen<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+	/// How many profiles signed calls have registered in the current block.
+	#[pallet::storage]
+	pub type RegistrationsThisBlock<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Aliases that resolve to the account that added them with `add_redirect`, keyed by
+	/// their lowercase canonical form.
+	#[pallet::storage]
+	pub type Redirects<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxLength>, T::AccountId, OptionQuery>;
+
+	/// The casing each alias in [`Redirects`] was added with, keyed by its canonical form.
+	#[pallet::storage]
+	pub type DisplayByCanonical<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxLength>,
+		BoundedVec<u8, T::MaxLength>,
+		OptionQuery,
+	>;
+
+	/// Accounts approved to register profiles. While it is empty anyone may register.
+	#[pallet::storage]
+	pub type AllowedRegistrants<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// The canonical keys of the [`Redirects`] each account holds, so they can be counted
+	/// against `MaxRedirects` and dropped with the profile.
+	#[pallet::storage]
+	pub type RedirectsOf<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<BoundedVec<u8, T::MaxLength>, T::MaxRedirects>,
+		ValueQuery,
+	>;
+
+	/// The parent that registered each sub-account's profile with `register_sub`.
+	#[pallet::storage]
+	pub type SubOwner<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// The first account holding each `(name, age, title)`, keyed by the hash of that triple.
+	#[pallet::storage]
+	pub type ProfileHashes<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, T::AccountId, OptionQuery>;
+
+	/// Open `offer_transfer` offers by the offering account: the recipient and the block the
+	/// offer was made in.
+	#[pallet::storage]
+	pub type TransferOffers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		(T::AccountId, BlockNumberFor<T>),
+		OptionQuery,
+	>;
+
+	/// The stage of an unfinished `force_rebuild_indexes` run.
+	#[pallet::storage]
+	pub type RebuildCursor<T: Config> = StorageValue<_, RebuildStage<T::AccountId>, OptionQuery>;
+
+	/// The last account walked by an unfinished `sanitize_names` run.
+	#[pallet::storage]
+	pub type SanitizeCursor<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// Minimum ages the force origin has set for individual titles.
+	#[pallet::storage]
+	pub type TitleMinAge<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxTitleLength>, u8, OptionQuery>;
+
+	/// Titles the force origin has assigned to age brackets, keyed by the bracket's lowest
+	/// age. A bracket runs up to the next key.
+	#[pallet::storage]
+	pub type AgeTitleBrackets<T: Config> =
+		StorageMap<_, Twox64Concat, u8, BoundedVec<u8, T::MaxTitleLength>, OptionQuery>;
+
+	/// Pending `commit_name` commitments: the committed hash and the block it was made in.
+	#[pallet::storage]
+	pub type Commitments<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::Hash, BlockNumberFor<T>), OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		UserInfoAdded(T::AccountId),
+		/// The account registered its profile; the second field is its new [`Seq`] number.
+		UserInfoAdded(T::AccountId, u32),
+		/// The account edited its profile; the second field is its new [`Seq`] number.
+		UserInfoUpdated(T::AccountId, u32),
+		/// The force origin overwrote this account's name.
+		UserInfoForced(T::AccountId),
+		/// The second account's profile was merged into the first's and removed.
+		ProfilesMerged(T::AccountId, T::AccountId),
+		/// The force origin flagged this account's profile as disputed.
+		ProfileFlagged(T::AccountId),
+		/// The dispute flag was lifted from this account's profile.
+		ProfileUnflagged(T::AccountId),
+		/// This account committed to a name it will reveal later.
+		NameCommitted(T::AccountId),
+		/// The owner locked their profile against edits.
+		ProfileLocked(T::AccountId),
+		/// The owner unlocked their profile.
+		ProfileUnlocked(T::AccountId),
+		/// The owner set a claim of this type, replacing any earlier one.
+		ClaimAdded(T::AccountId, ClaimType),
+		/// The owner removed their claim of this type.
+		ClaimRemoved(T::AccountId, ClaimType),
+		/// The account signalled it is active.
+		Heartbeat(T::AccountId),
+		/// The owner set or cleared their pronoun.
+		PronounSet(T::AccountId, Option<Pronoun>),
+		/// The force origin added an account to, or removed it from, [`AllowedRegistrants`].
+		RegistrantAllowed(T::AccountId, bool),
+		/// The force origin set or cleared the runtime overrides.
+		RuntimeConfigSet(Option<RuntimeConfig>),
+		/// The owner chose whether their profile appears in enumeration reads.
+		DiscoverableSet(T::AccountId, bool),
+		/// The owner chose who may read their profile.
+		VisibilitySet(T::AccountId, Visibility),
+		/// The owner chose whether their age is hidden from runtime API reads.
+		AgeVisibilitySet(T::AccountId, bool),
+		/// Trailing padding was trimmed from this many stored names.
+		NamesSanitized(u32),
+		/// The first account offered its profile to the second.
+		TransferOffered(T::AccountId, T::AccountId),
+		/// The second account accepted the first's profile.
+		TransferAccepted(T::AccountId, T::AccountId),
+		/// The first account withdrew its offer to the second.
+		TransferCancelled(T::AccountId, T::AccountId),
+		/// The account added this alias for its profile.
+		RedirectAdded(T::AccountId, BoundedVec<u8, T::MaxLength>),
+		/// The account removed this alias.
+		RedirectRemoved(T::AccountId, BoundedVec<u8, T::MaxLength>),
+		/// The profile of this account was removed because the account no longer exists.
+		DeadProfileCleaned(T::AccountId),
+		/// The first account registered a profile for its sub-account, the second.
+		SubRegistered(T::AccountId, T::AccountId),
+		/// The first account removed its sub-account's profile.
+		SubCleared(T::AccountId, T::AccountId),
+		/// `force_rebuild_indexes` counted this many profiles; `true` once the rebuild is
+		/// complete.
+		IndexesRebuilt(u32, bool),
+		/// `force_emit_diagnostics` counted this many stored profiles while [`LengthHistogram`]
+		/// accounts for the second number; `false` if `AdminWeightBudget` ran out before every
+		/// profile was counted.
+		Diagnostics(u32, u32, bool),
+		/// The minimum age for this title was set, or removed if `None`.
+		TitleMinAgeSet(BoundedVec<u8, T::MaxTitleLength>, Option<u8>),
+		/// The force origin set or cleared the title of the bracket starting at this age.
+		AgeTitleBracketSet(u8, Option<BoundedVec<u8, T::MaxTitleLength>>),
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		TooLong,
+		UserNotAdded,
+		/// Minors may not hold this title.
+		TitleNotAllowedForAge,
+		/// The target account could not be resolved from the given address.
+		InvalidRecipient,
+		/// The two accounts given must differ.
+		SameAccount,
+		/// Flagged profiles may not be renamed by their owner.
+		ProfileFlagged,
+		/// The caller has no pending name commitment.
+		NoCommitment,
+		/// The revealed name and salt do not hash to the committed value.
+		CommitmentMismatch,
+		/// `MinCommitDelay` has not yet passed since the commitment.
+		RevealTooEarly,
+		/// The owner has locked this profile against their own edits.
+		ProfileLocked,
+		/// The profile's age is below the minimum set for this title.
+		TitleAgeRequirement,
+		/// The name could be mistaken for an account address.
+		NameLooksLikeAddress,
+		/// Another account already holds this exact name, age and title.
+		DuplicateProfile,
+		/// There is no open transfer offer between these accounts.
+		NoTransferOffer,
+		/// The transfer offer is older than `TransferOfferTimeout`.
+		TransferOfferExpired,
+		/// The recipient already has a profile of their own.
+		RecipientHasProfile,
+		/// The caller did not register this sub-account's profile.
+		NotSubOwner,
+		/// The account still exists, so its profile may not be cleaned up.
+		AccountStillAlive,
+		/// Another account already holds this alias.
+		AliasTaken,
+		/// The caller does not hold this alias.
+		NotAliasOwner,
+		/// The caller already holds `MaxRedirects` aliases.
+		TooManyRedirects,
+		/// `MaxRegistrationsPerBlock` profiles were already registered in this block.
+		RateLimited,
+		/// The claim value is not in the format its type requires.
+		MalformedClaim,
+		/// The profile already carries `MaxClaims` claims.
+		TooManyClaims,
+		/// The profile has no claim of this type.
+		NoSuchClaim,
+		/// The blob is not exactly one SCALE-encoded profile within this chain's limits.
+		InvalidProfileBlob,
+		/// Registration is restricted and the caller is not in [`AllowedRegistrants`].
+		NotAllowed,
+		/// The name is shorter than the configured minimum.
+		TooShort,
+		/// The overrides are inconsistent or exceed the compile-time limits.
+		InvalidRuntimeConfig,
+		/// `HeartbeatInterval` has not yet passed since the caller's last heartbeat.
+		HeartbeatTooSoon,
+		/// The profile was registered with `register_sub` and only its parent may clear it.
+		SubProfile,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			<RegistrationsThisBlock<T>>::kill();
+			T::DbWeight::get().writes(1)
+		}
+
+		fn integrity_test() {
+			assert!(
+				T::MaxTitleLength::get() >= T::MaxLength::get(),
+				"MaxTitleLength must not be shorter than MaxLength",
+			);
+			assert!(
+				T::DefaultTitle::get().len() <= T::MaxTitleLength::get() as usize,
+				"DefaultTitle must not be longer than MaxTitleLength",
+			);
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
 		pub fn insert_user_info(
 			origin: OriginFor<T>,
 			name: Vec<u8>,
@@ -62,14 +531,1487 @@ pub mod pallet {
 			title: Vec<u8>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_allowed_registrant(&sender)?;
+			Self::note_registration()?;
+			let title = if T::AutoTitleFromAge::get() {
+				Self::title_for_age(age).map(BoundedVec::into_inner).unwrap_or_default()
+			} else {
+				title
+			};
+			Self::do_insert(&sender, name, age, title)
+		}
+
+		/// Apply only the `Some` fields of `changes` to the caller's existing profile.
+		#[pallet::call_index(1)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn update_profile(origin: OriginFor<T>, changes: ProfileUpdate) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_update(&sender, changes)
+		}
+
+		/// Remove the caller's title while keeping the rest of their profile.
+		#[pallet::call_index(3)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn clear_title(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			<AccountToUserInfo<T>>::try_mutate(&sender, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				ensure!(!user.locked, Error::<T>::ProfileLocked);
+				let old_hash = Self::profile_hash(user);
+				user.title = BoundedVec::default();
+				let new_hash = Self::profile_hash(user);
+				Self::ensure_not_duplicate(&sender, &new_hash)?;
+				Self::note_profile_hash(&sender, Some(old_hash), Some(new_hash));
+				Ok(())
+			})?;
+			let seq = Self::next_seq(&sender);
+			Self::deposit_profile_event(
+				&sender,
+				&sender,
+				Event::<T>::UserInfoUpdated(sender.clone(), seq),
+			);
+			Ok(())
+		}
+
+		/// Trim trailing null and whitespace bytes from the names of up to `limit` profiles.
+		///
+		/// Names that are already clean are left untouched, so calling this again is a no-op. A
+		/// run that stops at `limit` resumes where it left off on the next call, and the call
+		/// after the last profile starts over from the first.
+		#[pallet::call_index(2)]
+		#[pallet::weight(Pallet::<T>::admin_weight(*limit, Pallet::<T>::sanitize_item_weight()))]
+		pub fn sanitize_names(origin: OriginFor<T>, limit: u32) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let mut profiles = match <SanitizeCursor<T>>::take() {
+				Some(last) => {
+					<AccountToUserInfo<T>>::iter_from(<AccountToUserInfo<T>>::hashed_key_for(last))
+				},
+				None => <AccountToUserInfo<T>>::iter(),
+			};
+			let mut last = None;
+			let mut padded = Vec::new();
+			let (_, used) = Self::process_bounded(limit, Self::sanitize_item_weight(), || {
+				let (who, user) = profiles.next()?;
+				last = Some(who.clone());
+				if !Self::has_padding(&user.name) {
+					return Some(Self::item_weight(1, 0));
+				}
+				padded.push((who, user));
+				Some(Self::sanitize_item_weight())
+			});
+			if profiles.next().is_some() {
+				<SanitizeCursor<T>>::set(last);
+			}
+			let sanitized = padded.len() as u32;
+			for (who, mut user) in padded {
+				let old_name = user.name.clone();
+				let old_hash = Self::profile_hash(&user);
+				while Self::has_padding(&user.name) {
+					user.name.pop();
+				}
+				Self::note_name_length(&who, Some(old_name.len()), Some(user.name.len()));
+				Self::note_profile_hash(&who, Some(old_hash), Some(Self::profile_hash(&user)));
+				Self::note_rename(&who, &old_name, &user.name);
+				<AccountToUserInfo<T>>::insert(&who, user);
+			}
+			Self::deposit_event(Event::<T>::NamesSanitized(sanitized));
+			Ok(Self::admin_post_info(used))
+		}
+
+		/// Overwrite the name of `target`'s existing profile, for emergency fixes by support
+		/// staff.
+		#[pallet::call_index(4)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn force_set_name(
+			origin: OriginFor<T>,
+			target: AccountIdLookupOf<T>,
+			name: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target).map_err(|_| Error::<T>::InvalidRecipient)?;
 			let bounded_name =
 				BoundedVec::<u8, T::MaxLength>::try_from(name).map_err(|_| Error::<T>::TooLong)?;
-			let bounded_title =
-				BoundedVec::<u8, T::MaxLength>::try_from(title).map_err(|_| Error::<T>::TooLong)?;
-			let user = User { name: bounded_name, age, title: bounded_title };
+			let old_name = <AccountToUserInfo<T>>::try_mutate(
+				&target,
+				|maybe_user| -> Result<_, DispatchError> {
+					let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+					Self::note_name_length(
+						&target,
+						Some(user.name.len()),
+						Some(bounded_name.len()),
+					);
+					let old_hash = Self::profile_hash(user);
+					let old_name = core::mem::replace(&mut user.name, bounded_name.clone());
+					Self::note_profile_hash(
+						&target,
+						Some(old_hash),
+						Some(Self::profile_hash(user)),
+					);
+					Ok(old_name)
+				},
+			)?;
+			Self::note_rename(&target, &old_name, &bounded_name);
+			Self::deposit_account_event(&target, Event::<T>::UserInfoForced(target.clone()));
+			Ok(Pays::No.into())
+		}
+
+		/// Merge `secondary`'s profile into `primary`'s, e.g. after a key rotation.
+		///
+		/// The primary profile is kept if there is one; otherwise the secondary profile moves
+		/// over to the primary account. Either way the secondary account is left without a
+		/// profile.
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 6).ref_time())]
+		pub fn force_merge_profiles(
+			origin: OriginFor<T>,
+			primary: AccountIdLookupOf<T>,
+			secondary: AccountIdLookupOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let primary = T::Lookup::lookup(primary).map_err(|_| Error::<T>::InvalidRecipient)?;
+			let secondary =
+				T::Lookup::lookup(secondary).map_err(|_| Error::<T>::InvalidRecipient)?;
+			ensure!(primary != secondary, Error::<T>::SameAccount);
+			let secondary_user =
+				<AccountToUserInfo<T>>::get(&secondary).ok_or(Error::<T>::UserNotAdded)?;
+			let moving = !<AccountToUserInfo<T>>::contains_key(&primary);
+			if moving {
+				<Claims<T>>::swap(&secondary, &primary);
+				Self::move_redirects(&secondary, &primary);
+			}
+			Self::remove_profile(&secondary);
+			<SubOwner<T>>::remove(&secondary);
+			if moving {
+				Self::note_name_length(&primary, None, Some(secondary_user.name.len()));
+				Self::note_profile_hash(&primary, None, Some(Self::profile_hash(&secondary_user)));
+				<AccountToUserInfo<T>>::insert(&primary, secondary_user);
+			}
+			Self::deposit_account_event(
+				&primary,
+				Event::<T>::ProfilesMerged(primary.clone(), secondary),
+			);
+			Ok(Pays::No.into())
+		}
+
+		/// Mark `target`'s profile as disputed, recording `reason`. The owner can no longer
+		/// rename it until it is unflagged.
+		#[pallet::call_index(6)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn flag_profile(
+			origin: OriginFor<T>,
+			target: AccountIdLookupOf<T>,
+			reason: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target).map_err(|_| Error::<T>::InvalidRecipient)?;
+			let reason = BoundedVec::<u8, T::MaxLength>::try_from(reason)
+				.map_err(|_| Error::<T>::TooLong)?;
+			<AccountToUserInfo<T>>::try_mutate(&target, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				user.flagged = true;
+				user.flag_reason = reason;
+				Ok(())
+			})?;
+			Self::deposit_account_event(&target, Event::<T>::ProfileFlagged(target.clone()));
+			Ok(Pays::No.into())
+		}
+
+		/// Lift the dispute flag from `target`'s profile.
+		#[pallet::call_index(7)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn unflag_profile(
+			origin: OriginFor<T>,
+			target: AccountIdLookupOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target).map_err(|_| Error::<T>::InvalidRecipient)?;
+			<AccountToUserInfo<T>>::try_mutate(&target, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				user.flagged = false;
+				user.flag_reason = BoundedVec::default();
+				Ok(())
+			})?;
+			Self::deposit_account_event(&target, Event::<T>::ProfileUnflagged(target.clone()));
+			Ok(Pays::No.into())
+		}
+
+		/// Commit to a name without revealing it, to avoid being front-run.
+		///
+		/// `hash` must be `T::Hashing` applied to the SCALE encoding of the `(name, salt)` pair
+		/// later passed to `reveal_name`. A new commitment replaces any pending one.
+		#[pallet::call_index(8)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn commit_name(origin: OriginFor<T>, hash: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			<Commitments<T>>::insert(&sender, (hash, now));
+			Self::deposit_account_event(&sender, Event::<T>::NameCommitted(sender.clone()));
+			Ok(())
+		}
+
+		/// Register a profile whose name was committed to at least `MinCommitDelay` blocks ago.
+		#[pallet::call_index(9)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
+		pub fn reveal_name(
+			origin: OriginFor<T>,
+			name: Vec<u8>,
+			salt: Vec<u8>,
+			age: u8,
+			title: Vec<u8>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let (hash, committed_at) =
+				<Commitments<T>>::get(&sender).ok_or(Error::<T>::NoCommitment)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now >= committed_at.saturating_add(T::MinCommitDelay::get()),
+				Error::<T>::RevealTooEarly
+			);
+			ensure!(T::Hashing::hash_of(&(&name, &salt)) == hash, Error::<T>::CommitmentMismatch);
+			Self::ensure_allowed_registrant(&sender)?;
+			Self::note_registration()?;
+			Self::do_insert(&sender, name, age, title)?;
+			<Commitments<T>>::remove(&sender);
+			Ok(())
+		}
+
+		/// Freeze the caller's profile so their own edits are refused until
+		/// `unlock_profile`. The force origin can still edit it.
+		#[pallet::call_index(10)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn lock_profile(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::set_locked(&sender, true)?;
+			Self::deposit_account_event(&sender, Event::<T>::ProfileLocked(sender.clone()));
+			Ok(())
+		}
+
+		/// Allow the caller to edit their profile again.
+		#[pallet::call_index(11)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn unlock_profile(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::set_locked(&sender, false)?;
+			Self::deposit_account_event(&sender, Event::<T>::ProfileUnlocked(sender.clone()));
+			Ok(())
+		}
+
+		/// Hide or reveal the caller's age in runtime API reads.
+		#[pallet::call_index(12)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn set_age_visibility(origin: OriginFor<T>, hidden: bool) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			<AccountToUserInfo<T>>::try_mutate(&sender, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				ensure!(!user.locked, Error::<T>::ProfileLocked);
+				user.age_hidden = hidden;
+				Ok(())
+			})?;
+			Self::deposit_account_event(
+				&sender,
+				Event::<T>::AgeVisibilitySet(sender.clone(), hidden),
+			);
+			Ok(())
+		}
+
+		/// Require holders of `title` to be at least `min_age`, or drop the requirement when
+		/// `min_age` is `None`. Existing profiles are not re-checked.
+		#[pallet::call_index(13)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_title_min_age(
+			origin: OriginFor<T>,
+			title: Vec<u8>,
+			min_age: Option<u8>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let title = BoundedVec::<u8, T::MaxTitleLength>::try_from(title)
+				.map_err(|_| Error::<T>::TooLong)?;
+			<TitleMinAge<T>>::set(&title, min_age);
+			Self::deposit_event(Event::<T>::TitleMinAgeSet(title, min_age));
+			Ok(Pays::No.into())
+		}
+
+		/// Recount [`LengthHistogram`] and refill [`ProfileHashes`] from the stored profiles,
+		/// handling at most `limit` items. A fresh run first removes the old index entries one
+		/// item each, then counts one profile per item.
+		///
+		/// A run that stops at `limit` resumes where it left off on the next call; the
+		/// indexes are only consistent again once the `IndexesRebuilt` event reports completion.
+		/// Profiles may change in between: changes to accounts the run has not reached yet are
+		/// left for the run to count.
+		#[pallet::call_index(14)]
+		#[pallet::weight(Pallet::<T>::admin_weight(*limit, Pallet::<T>::rebuild_item_weight()))]
+		pub fn force_rebuild_indexes(
+			origin: OriginFor<T>,
+			limit: u32,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let stage = <RebuildCursor<T>>::take();
+			let mut clearing = matches!(stage, None | Some(RebuildStage::Clearing));
+			let mut last = match stage {
+				Some(RebuildStage::Counting(last)) => last,
+				_ => None,
+			};
+			let mut profiles = None;
+			let mut counted = 0;
+			let (_, used) = Self::process_bounded(limit, Self::rebuild_item_weight(), || {
+				if clearing {
+					if let Some(len) = <LengthHistogram<T>>::iter_keys().next() {
+						<LengthHistogram<T>>::remove(len);
+						return Some(Self::item_weight(1, 1));
+					}
+					if let Some(hash) = <ProfileHashes<T>>::iter_keys().next() {
+						<ProfileHashes<T>>::remove(hash);
+						return Some(Self::item_weight(1, 1));
+					}
+					clearing = false;
+				}
+				let profiles = profiles.get_or_insert_with(|| match &last {
+					Some(last) => <AccountToUserInfo<T>>::iter_from(
+						<AccountToUserInfo<T>>::hashed_key_for(last),
+					),
+					None => <AccountToUserInfo<T>>::iter(),
+				});
+				let (who, user) = profiles.next()?;
+				Self::note_name_length(&who, None, Some(user.name.len()));
+				Self::note_profile_hash(&who, None, Some(Self::profile_hash(&user)));
+				last = Some(who);
+				counted += 1;
+				Some(Self::rebuild_item_weight())
+			});
+			let complete =
+				!clearing && profiles.map_or(false, |mut profiles| profiles.next().is_none());
+			if !complete {
+				<RebuildCursor<T>>::put(if clearing {
+					RebuildStage::Clearing
+				} else {
+					RebuildStage::Counting(last)
+				});
+			}
+			Self::deposit_event(Event::<T>::IndexesRebuilt(counted, complete));
+			Ok(Self::admin_post_info(used))
+		}
+
+		/// Offer the caller's profile to `to`, who must accept it with `accept_transfer` within
+		/// `TransferOfferTimeout` blocks. A new offer replaces any earlier one.
+		#[pallet::call_index(15)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn offer_transfer(origin: OriginFor<T>, to: AccountIdLookupOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to).map_err(|_| Error::<T>::InvalidRecipient)?;
+			ensure!(sender != to, Error::<T>::SameAccount);
+			let user = <AccountToUserInfo<T>>::get(&sender).ok_or(Error::<T>::UserNotAdded)?;
+			ensure!(!user.locked, Error::<T>::ProfileLocked);
+			ensure!(!user.flagged, Error::<T>::ProfileFlagged);
+			let now = frame_system::Pallet::<T>::block_number();
+			<TransferOffers<T>>::insert(&sender, (to.clone(), now));
+			Self::deposit_profile_event(
+				&to,
+				&sender,
+				Event::<T>::TransferOffered(sender.clone(), to.clone()),
+			);
+			Ok(())
+		}
+
+		/// Take over the profile `from` offered to the caller. The caller must not have a
+		/// profile already and counts as registering one, so the registrant allowlist and
+		/// `MaxRegistrationsPerBlock` apply.
+		#[pallet::call_index(16)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(6, 5).ref_time())]
+		pub fn accept_transfer(origin: OriginFor<T>, from: AccountIdLookupOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let from = T::Lookup::lookup(from).map_err(|_| Error::<T>::InvalidRecipient)?;
+			let (to, offered_at) =
+				<TransferOffers<T>>::get(&from).ok_or(Error::<T>::NoTransferOffer)?;
+			ensure!(to == sender, Error::<T>::NoTransferOffer);
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now <= offered_at.saturating_add(T::TransferOfferTimeout::get()),
+				Error::<T>::TransferOfferExpired
+			);
+			ensure!(
+				!<AccountToUserInfo<T>>::contains_key(&sender),
+				Error::<T>::RecipientHasProfile
+			);
+			Self::ensure_allowed_registrant(&sender)?;
+			Self::note_registration()?;
+			let user = <AccountToUserInfo<T>>::take(&from).ok_or(Error::<T>::UserNotAdded)?;
+			let hash = Self::profile_hash(&user);
+			Self::note_profile_hash(&from, Some(hash), None);
+			Self::note_profile_hash(&sender, None, Some(hash));
+			Self::note_name_length(&from, Some(user.name.len()), None);
+			Self::note_name_length(&sender, None, Some(user.name.len()));
 			<AccountToUserInfo<T>>::insert(&sender, user);
-			Self::deposit_event(Event::<T>::UserInfoAdded(sender));
+			<Claims<T>>::swap(&from, &sender);
+			Self::move_redirects(&from, &sender);
+			<SubOwner<T>>::remove(&from);
+			<TransferOffers<T>>::remove(&from);
+			Self::deposit_profile_event(
+				&sender,
+				&sender,
+				Event::<T>::TransferAccepted(from, sender.clone()),
+			);
+			Ok(())
+		}
+
+		/// Withdraw the caller's open offer to `to`.
+		#[pallet::call_index(17)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn cancel_transfer(origin: OriginFor<T>, to: AccountIdLookupOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to).map_err(|_| Error::<T>::InvalidRecipient)?;
+			ensure!(
+				<TransferOffers<T>>::get(&sender).map_or(false, |(offered_to, _)| offered_to == to),
+				Error::<T>::NoTransferOffer
+			);
+			<TransferOffers<T>>::remove(&sender);
+			Self::deposit_profile_event(
+				&to,
+				&sender,
+				Event::<T>::TransferCancelled(sender.clone(), to.clone()),
+			);
 			Ok(())
 		}
+
+		/// Register a profile for `sub`, which must not have one yet, and record the caller as
+		/// its parent. Only the parent can remove it again, with `clear_sub`.
+		#[pallet::call_index(18)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
+		pub fn register_sub(
+			origin: OriginFor<T>,
+			sub: AccountIdLookupOf<T>,
+			name: Vec<u8>,
+			age: u8,
+			title: Vec<u8>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let sub = T::Lookup::lookup(sub).map_err(|_| Error::<T>::InvalidRecipient)?;
+			ensure!(sender != sub, Error::<T>::SameAccount);
+			ensure!(!<AccountToUserInfo<T>>::contains_key(&sub), Error::<T>::RecipientHasProfile);
+			Self::ensure_allowed_registrant(&sender)?;
+			Self::note_registration()?;
+			Self::do_insert(&sub, name, age, title)?;
+			<SubOwner<T>>::insert(&sub, &sender);
+			Self::deposit_account_event(&sub, Event::<T>::SubRegistered(sender, sub.clone()));
+			Ok(())
+		}
+
+		/// Remove the profile of a sub-account the caller registered with `register_sub`.
+		#[pallet::call_index(19)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
+		pub fn clear_sub(origin: OriginFor<T>, sub: AccountIdLookupOf<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let sub = T::Lookup::lookup(sub).map_err(|_| Error::<T>::InvalidRecipient)?;
+			ensure!(<SubOwner<T>>::get(&sub) == Some(sender.clone()), Error::<T>::NotSubOwner);
+			<SubOwner<T>>::remove(&sub);
+			ensure!(Self::remove_profile(&sub).is_some(), Error::<T>::UserNotAdded);
+			Self::deposit_account_event(&sub, Event::<T>::SubCleared(sender, sub.clone()));
+			Ok(())
+		}
+
+		/// Remove the profile and pending state of `target` once `frame_system` no longer
+		/// tracks the account, i.e. it has no providers or sufficients left. Anyone may call
+		/// this. Profiles registered with `register_sub` are left to their parent, as the sub
+		/// account need never have been funded.
+		#[pallet::call_index(20)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 5).ref_time())]
+		pub fn clean_dead(origin: OriginFor<T>, target: AccountIdLookupOf<T>) -> DispatchResult {
+			ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target).map_err(|_| Error::<T>::InvalidRecipient)?;
+			ensure!(
+				!frame_system::Pallet::<T>::account_exists(&target),
+				Error::<T>::AccountStillAlive
+			);
+			ensure!(!<SubOwner<T>>::contains_key(&target), Error::<T>::SubProfile);
+			ensure!(Self::remove_profile(&target).is_some(), Error::<T>::UserNotAdded);
+			<Commitments<T>>::remove(&target);
+			<TransferOffers<T>>::remove(&target);
+			Self::deposit_account_event(&target, Event::<T>::DeadProfileCleaned(target.clone()));
+			Ok(())
+		}
+
+		/// Set the caller's pronoun, or clear it with `None`.
+		#[pallet::call_index(21)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn set_pronoun(origin: OriginFor<T>, pronoun: Option<Pronoun>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			<AccountToUserInfo<T>>::try_mutate(&sender, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				ensure!(!user.locked, Error::<T>::ProfileLocked);
+				user.pronoun = pronoun;
+				Ok(())
+			})?;
+			Self::deposit_account_event(&sender, Event::<T>::PronounSet(sender.clone(), pronoun));
+			Ok(())
+		}
+
+		/// Make `alias` resolve to the caller's profile. Aliases are unique across accounts
+		/// regardless of ASCII case and follow the same rules as names. Re-adding an alias the
+		/// caller already holds only changes its display casing.
+		#[pallet::call_index(22)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2).ref_time())]
+		pub fn add_redirect(origin: OriginFor<T>, alias: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let alias =
+				BoundedVec::<u8, T::MaxLength>::try_from(alias).map_err(|_| Error::<T>::TooLong)?;
+			Self::ensure_name_allowed(&alias)?;
+			ensure!(<AccountToUserInfo<T>>::contains_key(&sender), Error::<T>::UserNotAdded);
+			let canonical = Self::canonical_alias(&alias);
+			match <Redirects<T>>::get(&canonical) {
+				Some(holder) => ensure!(holder == sender, Error::<T>::AliasTaken),
+				None => {
+					<RedirectsOf<T>>::try_mutate(&sender, |aliases| {
+						aliases
+							.try_push(canonical.clone())
+							.map_err(|_| Error::<T>::TooManyRedirects)
+					})?;
+					<Redirects<T>>::insert(&canonical, &sender);
+				},
+			}
+			<DisplayByCanonical<T>>::insert(&canonical, &alias);
+			Self::deposit_account_event(&sender, Event::<T>::RedirectAdded(sender.clone(), alias));
+			Ok(())
+		}
+
+		/// Drop an alias the caller added with `add_redirect`, given in any casing.
+		#[pallet::call_index(23)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
+		pub fn remove_redirect(origin: OriginFor<T>, alias: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let alias =
+				BoundedVec::<u8, T::MaxLength>::try_from(alias).map_err(|_| Error::<T>::TooLong)?;
+			let canonical = Self::canonical_alias(&alias);
+			ensure!(
+				<Redirects<T>>::get(&canonical) == Some(sender.clone()),
+				Error::<T>::NotAliasOwner
+			);
+			<Redirects<T>>::remove(&canonical);
+			<RedirectsOf<T>>::mutate(&sender, |aliases| aliases.retain(|held| *held != canonical));
+			let alias = <DisplayByCanonical<T>>::take(&canonical).unwrap_or(canonical);
+			Self::deposit_account_event(
+				&sender,
+				Event::<T>::RedirectRemoved(sender.clone(), alias),
+			);
+			Ok(())
+		}
+
+		/// Attach a claim of `claim_type` to the caller's profile, replacing any earlier claim
+		/// of the same type. `value` must be in the format the type requires.
+		#[pallet::call_index(24)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn add_claim(
+			origin: OriginFor<T>,
+			claim_type: ClaimType,
+			value: Vec<u8>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let user = <AccountToUserInfo<T>>::get(&sender).ok_or(Error::<T>::UserNotAdded)?;
+			ensure!(!user.locked, Error::<T>::ProfileLocked);
+			let value =
+				BoundedVec::<u8, T::ClaimLen>::try_from(value).map_err(|_| Error::<T>::TooLong)?;
+			ensure!(Self::claim_well_formed(claim_type, &value), Error::<T>::MalformedClaim);
+			<Claims<T>>::try_mutate(&sender, |claims| -> DispatchResult {
+				match claims.iter_mut().find(|(kind, _)| *kind == claim_type) {
+					Some(claim) => claim.1 = value,
+					None => claims
+						.try_push((claim_type, value))
+						.map_err(|_| Error::<T>::TooManyClaims)?,
+				}
+				Ok(())
+			})?;
+			Self::deposit_account_event(
+				&sender,
+				Event::<T>::ClaimAdded(sender.clone(), claim_type),
+			);
+			Ok(())
+		}
+
+		/// Remove the caller's claim of `claim_type`.
+		#[pallet::call_index(25)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn remove_claim(origin: OriginFor<T>, claim_type: ClaimType) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let user = <AccountToUserInfo<T>>::get(&sender).ok_or(Error::<T>::UserNotAdded)?;
+			ensure!(!user.locked, Error::<T>::ProfileLocked);
+			<Claims<T>>::try_mutate_exists(&sender, |maybe_claims| -> DispatchResult {
+				let claims = maybe_claims.as_mut().ok_or(Error::<T>::NoSuchClaim)?;
+				let index = claims
+					.iter()
+					.position(|(kind, _)| *kind == claim_type)
+					.ok_or(Error::<T>::NoSuchClaim)?;
+				claims.remove(index);
+				if claims.is_empty() {
+					*maybe_claims = None;
+				}
+				Ok(())
+			})?;
+			Self::deposit_account_event(
+				&sender,
+				Event::<T>::ClaimRemoved(sender.clone(), claim_type),
+			);
+			Ok(())
+		}
+
+		/// Record that the caller, who must have a profile, is active in the current block.
+		#[pallet::call_index(26)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn heartbeat(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(<AccountToUserInfo<T>>::contains_key(&sender), Error::<T>::UserNotAdded);
+			let now = frame_system::Pallet::<T>::block_number();
+			if let Some(last) = <LastSeen<T>>::get(&sender) {
+				ensure!(
+					now >= last.saturating_add(T::HeartbeatInterval::get()),
+					Error::<T>::HeartbeatTooSoon
+				);
+			}
+			<LastSeen<T>>::insert(&sender, now);
+			Self::deposit_account_event(&sender, Event::<T>::Heartbeat(sender.clone()));
+			Ok(())
+		}
+
+		/// Choose who may read the caller's profile through the runtime API. Private profiles
+		/// still count towards duplicate checks.
+		#[pallet::call_index(27)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn set_visibility(origin: OriginFor<T>, visibility: Visibility) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			<AccountToUserInfo<T>>::try_mutate(&sender, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				ensure!(!user.locked, Error::<T>::ProfileLocked);
+				user.visibility = visibility;
+				Ok(())
+			})?;
+			Self::deposit_account_event(
+				&sender,
+				Event::<T>::VisibilitySet(sender.clone(), visibility),
+			);
+			Ok(())
+		}
+
+		/// Override the name length limits, or go back to the constants with `None`. The
+		/// maximum may not exceed `MaxLength`. Stored profiles are not re-checked.
+		#[pallet::call_index(28)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_runtime_config(
+			origin: OriginFor<T>,
+			config: Option<RuntimeConfig>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			if let Some(config) = config {
+				ensure!(
+					config.min_length <= config.max_length
+						&& config.max_length <= T::MaxLength::get(),
+					Error::<T>::InvalidRuntimeConfig
+				);
+			}
+			<Overrides<T>>::set(config);
+			Self::deposit_event(Event::<T>::RuntimeConfigSet(config));
+			Ok(Pays::No.into())
+		}
+
+		/// Give the age bracket starting at `min_age` a title, or remove the bracket with `None`.
+		#[pallet::call_index(29)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_age_title_bracket(
+			origin: OriginFor<T>,
+			min_age: u8,
+			title: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let title = title
+				.map(BoundedVec::<u8, T::MaxTitleLength>::try_from)
+				.transpose()
+				.map_err(|_| Error::<T>::TooLong)?;
+			<AgeTitleBrackets<T>>::set(min_age, title.clone());
+			Self::deposit_event(Event::<T>::AgeTitleBracketSet(min_age, title));
+			Ok(Pays::No.into())
+		}
+
+		/// Apply `request.changes` like `update_profile`, or with `validate_only` set, only
+		/// report whether they would be accepted.
+		#[pallet::call_index(30)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 3).ref_time())]
+		pub fn edit(origin: OriginFor<T>, request: EditRequest) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			if !request.validate_only {
+				return Self::do_update(&sender, request.changes);
+			}
+			let user = <AccountToUserInfo<T>>::get(&sender).ok_or(Error::<T>::UserNotAdded)?;
+			Self::updated_profile(&sender, &user, request.changes).map(|_| ())
+		}
+
+		/// Approve `who` to register, or withdraw the approval. Once any account is approved,
+		/// only approved accounts may register.
+		#[pallet::call_index(31)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 2).ref_time())]
+		pub fn set_registrant_allowed(
+			origin: OriginFor<T>,
+			who: AccountIdLookupOf<T>,
+			allowed: bool,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who).map_err(|_| Error::<T>::InvalidRecipient)?;
+			if allowed {
+				<AllowedRegistrants<T>>::insert(&who, ());
+			} else {
+				<AllowedRegistrants<T>>::remove(&who);
+			}
+			Self::deposit_account_event(&who, Event::<T>::RegistrantAllowed(who.clone(), allowed));
+			Ok(Pays::No.into())
+		}
+
+		/// Count the stored profiles and report them next to the [`LengthHistogram`] total in a
+		/// `Diagnostics` event, so operators can check the index from event logs.
+		#[pallet::call_index(32)]
+		#[pallet::weight(Pallet::<T>::admin_weight(u32::MAX, Pallet::<T>::item_weight(1, 0)))]
+		pub fn force_emit_diagnostics(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let mut profiles = <AccountToUserInfo<T>>::iter_keys();
+			let (users, used) = Self::process_bounded(u32::MAX, Self::item_weight(1, 0), || {
+				profiles.next().map(|_| Self::item_weight(1, 0))
+			});
+			let complete = profiles.next().is_none();
+			let indexed = Self::stats().user_count;
+			Self::deposit_event(Event::<T>::Diagnostics(users, indexed, complete));
+			Ok(Self::admin_post_info(used))
+		}
+
+		/// Register the caller with a profile produced by `export_profile`, here or on another
+		/// chain. The blob goes through the same checks as `insert_user_info`; flags and locks
+		/// in it are ignored.
+		#[pallet::call_index(33)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3).ref_time())]
+		pub fn import_profile(origin: OriginFor<T>, blob: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let imported = User::<T>::decode_all(&mut &blob[..])
+				.map_err(|_| Error::<T>::InvalidProfileBlob)?;
+			Self::ensure_allowed_registrant(&sender)?;
+			Self::note_registration()?;
+			Self::do_insert(
+				&sender,
+				imported.name.into_inner(),
+				imported.age,
+				imported.title.into_inner(),
+			)?;
+			<AccountToUserInfo<T>>::mutate(&sender, |maybe_user| {
+				if let Some(user) = maybe_user {
+					user.age_hidden = imported.age_hidden;
+					user.pronoun = imported.pronoun;
+					user.visibility = imported.visibility;
+					user.discoverable = imported.discoverable;
+				}
+			});
+			Ok(())
+		}
+
+		/// Choose whether the caller's profile appears in enumeration and search reads such as
+		/// `names_with_prefix_paged`.
+		#[pallet::call_index(34)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn set_discoverable(origin: OriginFor<T>, discoverable: bool) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			<AccountToUserInfo<T>>::try_mutate(&sender, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				ensure!(!user.locked, Error::<T>::ProfileLocked);
+				user.discoverable = discoverable;
+				Ok(())
+			})?;
+			Self::deposit_account_event(
+				&sender,
+				Event::<T>::DiscoverableSet(sender.clone(), discoverable),
+			);
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// All `#[pallet::constant]` values, so clients need not hardcode them.
+		pub fn config_values() -> NickConfig {
+			NickConfig {
+				max_length: T::MaxLength::get(),
+				max_title_length: T::MaxTitleLength::get(),
+				adult_age: T::AdultAge::get(),
+				min_commit_delay: T::MinCommitDelay::get().saturated_into(),
+				transfer_offer_timeout: T::TransferOfferTimeout::get().saturated_into(),
+				max_redirects: T::MaxRedirects::get(),
+				max_registrations_per_block: T::MaxRegistrationsPerBlock::get(),
+				max_claims: T::MaxClaims::get(),
+				claim_len: T::ClaimLen::get(),
+				heartbeat_interval: T::HeartbeatInterval::get().saturated_into(),
+				admin_weight_budget: T::AdminWeightBudget::get(),
+				max_batch: T::MaxBatch::get(),
+			}
+		}
+
+		/// The profile of `who`, if they have registered one. A hidden age reads as `0`.
+		pub fn whoami(who: &T::AccountId) -> Option<User<T>> {
+			Self::whoami_as(who, None)
+		}
+
+		/// Like [`Self::whoami`], but a private profile is returned when `viewer` is its owner.
+		pub fn whoami_as(who: &T::AccountId, viewer: Option<&T::AccountId>) -> Option<User<T>> {
+			Self::readable(who, viewer).map(Self::public_view)
+		}
+
+		/// `who`'s profile as stored, SCALE-encoded for `import_profile`, when `viewer` is its
+		/// owner. Unlike [`Self::whoami`], a hidden age and a private profile are exported as
+		/// they are, so nobody else gets one.
+		pub fn export_profile(
+			who: &T::AccountId,
+			viewer: Option<&T::AccountId>,
+		) -> Option<Vec<u8>> {
+			if viewer != Some(who) {
+				return None;
+			}
+			<AccountToUserInfo<T>>::get(who).map(|user| user.encode())
+		}
+
+		/// Like [`Self::whoami`], but falls back to an empty profile for unregistered accounts.
+		pub fn whoami_or_default(who: &T::AccountId) -> User<T> {
+			Self::whoami(who).unwrap_or_default()
+		}
+
+		/// Whether `who` is younger than `AdultAge`, if they have a profile with a visible age.
+		pub fn is_minor(who: &T::AccountId) -> Option<bool> {
+			Self::readable(who, None)
+				.filter(|user| !user.age_hidden)
+				.map(|user| user.age < T::AdultAge::get())
+		}
+
+		/// The number of bytes `who`'s profile occupies in storage, if they have a public one.
+		pub fn encoded_len_of(who: &T::AccountId) -> Option<u32> {
+			Self::readable(who, None).map(|user| user.encoded_size() as u32)
+		}
+
+		/// Up to `limit` titles with the number of profiles holding each, in byte order of the
+		/// title.
+		///
+		/// There is no title index, so this walks every profile; it is meant for off-chain
+		/// dashboards only.
+		pub fn title_counts(limit: u32) -> Vec<(Vec<u8>, u32)> {
+			let mut counts = BTreeMap::<Vec<u8>, u32>::new();
+			for user in <AccountToUserInfo<T>>::iter_values() {
+				*counts.entry(user.title.into_inner()).or_default() += 1;
+			}
+			counts.into_iter().take(limit as usize).collect()
+		}
+
+		/// Cut `bytes` to at most `max` bytes without splitting a UTF-8 sequence, for display
+		/// previews of long names.
+		pub fn safe_truncate(bytes: &[u8], max: usize) -> Vec<u8> {
+			if bytes.len() <= max {
+				return bytes.to_vec();
+			}
+			let mut end = max;
+			// Back off while the first dropped byte continues a sequence started before it.
+			while end > 0 && bytes[end] & 0b1100_0000 == 0b1000_0000 {
+				end -= 1;
+			}
+			bytes[..end].to_vec()
+		}
+
+		/// The first account among up to `limit` profiles whose stored name is exactly `name`.
+		///
+		/// This is a linear scan for diagnostics, not a lookup to rely on in dispatchables.
+		pub fn find_account_by_scan(name: &[u8], limit: u32) -> Option<T::AccountId> {
+			<AccountToUserInfo<T>>::iter()
+				.take(limit as usize)
+				.find(|(_, user)| user.name.as_slice() == name)
+				.map(|(who, _)| who)
+		}
+
+		/// The public, discoverable profiles among the next `limit` stored ones whose name
+		/// starts with `prefix`, as `(name, account)`, plus a cursor to pass as `start` for the
+		/// next page.
+		///
+		/// The cursor is `None` once every profile has been scanned. A page that reads nothing,
+		/// as with a `limit` of zero, returns a cursor to the same place it started.
+		///
+		/// Pages follow storage order, not name order, and names match byte for byte.
+		pub fn names_with_prefix_paged(
+			prefix: &[u8],
+			start: Option<Vec<u8>>,
+			limit: u32,
+		) -> (Vec<(Vec<u8>, T::AccountId)>, Option<Vec<u8>>) {
+			// The map's own prefix sorts before every key, so it resumes from the first profile.
+			let start = start.unwrap_or_else(|| <AccountToUserInfo<T>>::final_prefix().to_vec());
+			let mut profiles = <AccountToUserInfo<T>>::iter_from(start.clone());
+			let mut matches = Vec::new();
+			let mut last = None;
+			for (who, user) in profiles.by_ref().take(limit as usize) {
+				if user.visibility == Visibility::Public
+					&& user.discoverable
+					&& user.name.starts_with(prefix)
+				{
+					matches.push((user.name.into_inner(), who.clone()));
+				}
+				last = Some(who);
+			}
+			let cursor = profiles
+				.next()
+				.map(|_| last.map_or(start, <AccountToUserInfo<T>>::hashed_key_for));
+			(matches, cursor)
+		}
+
+		/// A digest of `who`'s profile, so clients can skip refetching when it has not changed.
+		/// It is `T::Hashing` (blake2-256 in the node runtime) over the SCALE-encoded profile as
+		/// [`Self::whoami`] returns it, so a hidden age cannot be guessed from it.
+		pub fn profile_digest(who: &T::AccountId) -> Option<T::Hash> {
+			Self::whoami(who).map(|user| T::Hashing::hash_of(&user))
+		}
+
+		/// The profiles of `accounts`, in input order, as [`Self::whoami`] returns them. Only the
+		/// first `MaxBatch` accounts are looked up.
+		pub fn users_batch(accounts: Vec<T::AccountId>) -> Vec<Option<User<T>>> {
+			accounts.iter().take(T::MaxBatch::get() as usize).map(Self::whoami).collect()
+		}
+
+		/// `who`'s name with the chain's SS58 prefix, so clients can show the name next to an
+		/// address encoded the same way everywhere.
+		pub fn user_with_address(who: &T::AccountId) -> Option<(Vec<u8>, u16)> {
+			Self::readable(who, None).map(|user| (user.name.into_inner(), T::SS58Prefix::get()))
+		}
+
+		/// Whether `who` could register a profile with a name and title of these lengths right
+		/// now, going by the checks of `insert_user_info` that need only the lengths: the
+		/// registrant allowlist, `MaxRegistrationsPerBlock`, the length limits and a lock.
+		/// Checks on the bytes themselves, such as name rules, title ages and duplicate
+		/// profiles, are not covered. On failure the code is the index of the [`Error`] variant
+		/// the call would fail with, as in `ModuleError::error[0]`.
+		pub fn can_register(who: &T::AccountId, name_len: u32, title_len: u32) -> Result<(), u8> {
+			let code = |error: Error<T>| error.encode()[0];
+			Self::ensure_allowed_registrant(who).map_err(|_| code(Error::<T>::NotAllowed))?;
+			if <RegistrationsThisBlock<T>>::get() >= T::MaxRegistrationsPerBlock::get() {
+				return Err(code(Error::<T>::RateLimited));
+			}
+			let limits = Self::runtime_config();
+			if name_len > limits.max_length || title_len > T::MaxTitleLength::get() {
+				return Err(code(Error::<T>::TooLong));
+			}
+			if name_len < limits.min_length {
+				return Err(code(Error::<T>::TooShort));
+			}
+			if <AccountToUserInfo<T>>::get(who).map_or(false, |user| user.locked) {
+				return Err(code(Error::<T>::ProfileLocked));
+			}
+			Ok(())
+		}
+
+		/// The account an alias added with `add_redirect` points to, if that account still has a
+		/// profile. Any casing of the alias resolves. Names themselves are not unique and have
+		/// no index, so only aliases resolve.
+		pub fn resolve(alias: &[u8]) -> Option<T::AccountId> {
+			let alias = BoundedVec::<u8, T::MaxLength>::try_from(alias.to_vec()).ok()?;
+			<Redirects<T>>::get(Self::canonical_alias(&alias))
+				.filter(|who| <AccountToUserInfo<T>>::contains_key(who))
+		}
+
+		/// `alias` in the casing its owner added it with.
+		pub fn display_alias(alias: &[u8]) -> Option<Vec<u8>> {
+			let alias = BoundedVec::<u8, T::MaxLength>::try_from(alias.to_vec()).ok()?;
+			<DisplayByCanonical<T>>::get(Self::canonical_alias(&alias)).map(BoundedVec::into_inner)
+		}
+
+		/// The limits in force: the stored overrides, or the constants when none are set.
+		pub fn runtime_config() -> RuntimeConfig {
+			<Overrides<T>>::get()
+				.unwrap_or(RuntimeConfig { min_length: 0, max_length: T::MaxLength::get() })
+		}
+
+		/// The title of the highest bracket in [`AgeTitleBrackets`] that starts at or below `age`.
+		pub fn title_for_age(age: u8) -> Option<BoundedVec<u8, T::MaxTitleLength>> {
+			<AgeTitleBrackets<T>>::iter()
+				.filter(|(min_age, _)| *min_age <= age)
+				.max_by_key(|(min_age, _)| *min_age)
+				.map(|(_, title)| title)
+		}
+
+		/// Whether `who` has a profile and sent a `heartbeat` within the last `window` blocks.
+		pub fn is_active(who: &T::AccountId, window: BlockNumberFor<T>) -> bool {
+			let now = frame_system::Pallet::<T>::block_number();
+			<AccountToUserInfo<T>>::contains_key(who)
+				&& <LastSeen<T>>::get(who).map_or(false, |last| now.saturating_sub(last) <= window)
+		}
+
+		/// The [`ProfileStatus`] of `who`'s profile, if they have a public one.
+		pub fn status_of(who: &T::AccountId) -> Option<ProfileStatus> {
+			Self::readable(who, None).map(|user| {
+				if user.flagged {
+					ProfileStatus::Flagged
+				} else if user.locked {
+					ProfileStatus::Locked
+				} else {
+					ProfileStatus::Active
+				}
+			})
+		}
+
+		/// Counters for dashboards. The profile count is summed from [`LengthHistogram`], so it
+		/// is only exact while no `force_rebuild_indexes` run is in progress.
+		pub fn stats() -> NickStats {
+			NickStats {
+				user_count: <LengthHistogram<T>>::iter_values()
+					.fold(0u32, |total, count| total.saturating_add(count)),
+			}
+		}
+
+		/// Every non-empty [`LengthHistogram`] bucket as `(length, count)`, shortest first.
+		pub fn length_histogram() -> Vec<(u32, u32)> {
+			let mut buckets: Vec<(u32, u32)> = <LengthHistogram<T>>::iter().collect();
+			buckets.sort_unstable();
+			buckets
+		}
+
+		/// The event topic under which events about `who` are indexed: `T::Hashing` applied to
+		/// the SCALE-encoded account id.
+		pub fn account_topic(who: &T::AccountId) -> T::Hash {
+			T::Hashing::hash_of(who)
+		}
+
+		/// Bump `who`'s [`Seq`] number and return the new value.
+		fn next_seq(who: &T::AccountId) -> u32 {
+			<Seq<T>>::mutate(who, |seq| {
+				*seq = seq.saturating_add(1);
+				*seq
+			})
+		}
+
+		/// The event topic under which events about a name are indexed: `T::Hashing` applied to
+		/// the raw name bytes, without a length prefix.
+		pub fn name_topic(name: &[u8]) -> T::Hash {
+			T::Hashing::hash(name)
+		}
+
+		/// Deposit `event` with the affected account as a topic, so clients can subscribe to
+		/// events about a single account.
+		fn deposit_account_event(who: &T::AccountId, event: Event<T>) {
+			Self::deposit_event_with_topics(&[Self::account_topic(who)], event);
+		}
+
+		/// Like [`Self::deposit_account_event`], but also indexed under the [`Self::name_topic`]
+		/// of the profile `holder` has once the call is done, if any.
+		fn deposit_profile_event(who: &T::AccountId, holder: &T::AccountId, event: Event<T>) {
+			match <AccountToUserInfo<T>>::get(holder) {
+				Some(user) => Self::deposit_event_with_topics(
+					&[Self::account_topic(who), Self::name_topic(&user.name)],
+					event,
+				),
+				None => Self::deposit_account_event(who, event),
+			}
+		}
+
+		fn deposit_event_with_topics(topics: &[T::Hash], event: Event<T>) {
+			let event = <<T as Config>::RuntimeEvent as From<Event<T>>>::from(event);
+			frame_system::Pallet::<T>::deposit_event_indexed(topics, event.into());
+		}
+
+		/// Refuse `who` when [`AllowedRegistrants`] is non-empty and does not list them.
+		fn ensure_allowed_registrant(who: &T::AccountId) -> DispatchResult {
+			ensure!(
+				<AllowedRegistrants<T>>::count() == 0 || <AllowedRegistrants<T>>::contains_key(who),
+				Error::<T>::NotAllowed
+			);
+			Ok(())
+		}
+
+		/// Count a registration by a signed call against `MaxRegistrationsPerBlock`.
+		fn note_registration() -> DispatchResult {
+			<RegistrationsThisBlock<T>>::try_mutate(|count| -> DispatchResult {
+				ensure!(*count < T::MaxRegistrationsPerBlock::get(), Error::<T>::RateLimited);
+				*count += 1;
+				Ok(())
+			})
+		}
+
+		/// Apply `changes` to `who`'s profile and keep the indexes in step.
+		fn do_update(who: &T::AccountId, changes: ProfileUpdate) -> DispatchResult {
+			<AccountToUserInfo<T>>::try_mutate(who, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				let updated = Self::updated_profile(who, user, changes)?;
+				Self::note_name_length(who, Some(user.name.len()), Some(updated.name.len()));
+				Self::note_profile_hash(
+					who,
+					Some(Self::profile_hash(user)),
+					Some(Self::profile_hash(&updated)),
+				);
+				Self::note_rename(who, &user.name, &updated.name);
+				*user = updated;
+				Ok(())
+			})?;
+			let seq = Self::next_seq(who);
+			Self::deposit_profile_event(who, who, Event::<T>::UserInfoUpdated(who.clone(), seq));
+			Ok(())
+		}
+
+		/// `user` with `changes` applied, if `who` may make them. Nothing is written.
+		fn updated_profile(
+			who: &T::AccountId,
+			user: &User<T>,
+			changes: ProfileUpdate,
+		) -> Result<User<T>, DispatchError> {
+			ensure!(!user.locked, Error::<T>::ProfileLocked);
+			let mut user = user.clone();
+			if let Some(name) = changes.name {
+				let name = BoundedVec::<u8, T::MaxLength>::try_from(name)
+					.map_err(|_| Error::<T>::TooLong)?;
+				Self::ensure_name_allowed(&name)?;
+				ensure!(!user.flagged || user.name == name, Error::<T>::ProfileFlagged);
+				user.name = name;
+			}
+			if let Some(age) = changes.age {
+				user.age = age;
+			}
+			if let Some(title) = changes.title {
+				user.title = BoundedVec::<u8, T::MaxTitleLength>::try_from(title)
+					.map_err(|_| Error::<T>::TooLong)?;
+			}
+			Self::ensure_title_allowed(user.age, &user.title)?;
+			Self::ensure_not_duplicate(who, &Self::profile_hash(&user))?;
+			Ok(user)
+		}
+
+		/// Create or overwrite the name, age and title of `who`'s profile.
+		fn do_insert(who: &T::AccountId, name: Vec<u8>, age: u8, title: Vec<u8>) -> DispatchResult {
+			<AccountToUserInfo<T>>::try_mutate(who, |maybe_user| -> DispatchResult {
+				let title = if title.is_empty() { T::DefaultTitle::get().to_vec() } else { title };
+				let bounded_name = BoundedVec::<u8, T::MaxLength>::try_from(name)
+					.map_err(|_| Error::<T>::TooLong)?;
+				Self::ensure_name_allowed(&bounded_name)?;
+				let bounded_title = BoundedVec::<u8, T::MaxTitleLength>::try_from(title)
+					.map_err(|_| Error::<T>::TooLong)?;
+				Self::ensure_title_allowed(age, &bounded_title)?;
+				let old_len = maybe_user.as_ref().map(|user| user.name.len());
+				let old_hash = maybe_user.as_ref().map(Self::profile_hash);
+				let mut user = maybe_user.clone().unwrap_or_default();
+				ensure!(!user.locked, Error::<T>::ProfileLocked);
+				ensure!(!user.flagged || user.name == bounded_name, Error::<T>::ProfileFlagged);
+				let old_name = core::mem::replace(&mut user.name, bounded_name);
+				user.age = age;
+				user.title = bounded_title;
+				let new_hash = Self::profile_hash(&user);
+				Self::ensure_not_duplicate(who, &new_hash)?;
+				// Callers of `ProfileRegistrar` may not roll back on error, so the indexes are
+				// only touched once every check has passed.
+				Self::note_name_length(who, old_len, Some(user.name.len()));
+				Self::note_profile_hash(who, old_hash, Some(new_hash));
+				if old_len.is_some() {
+					Self::note_rename(who, &old_name, &user.name);
+				}
+				*maybe_user = Some(user);
+				Ok(())
+			})?;
+			let seq = Self::next_seq(who);
+			Self::deposit_profile_event(who, who, Event::<T>::UserInfoAdded(who.clone(), seq));
+			Ok(())
+		}
+
+		/// Delete `who`'s profile, keeping the derived indexes in step.
+		fn remove_profile(who: &T::AccountId) -> Option<User<T>> {
+			let user = <AccountToUserInfo<T>>::take(who)?;
+			<Claims<T>>::remove(who);
+			<LastSeen<T>>::remove(who);
+			Self::remove_redirects(who);
+			Self::note_name_length(who, Some(user.name.len()), None);
+			Self::note_profile_hash(who, Some(Self::profile_hash(&user)), None);
+			Some(user)
+		}
+
+		/// Drop every alias `who` holds.
+		fn remove_redirects(who: &T::AccountId) {
+			for canonical in <RedirectsOf<T>>::take(who) {
+				<Redirects<T>>::remove(&canonical);
+				<DisplayByCanonical<T>>::remove(&canonical);
+			}
+		}
+
+		/// Point the aliases of `from`, who must not be `to`, at `to`. `to` is expected to hold
+		/// none.
+		fn move_redirects(from: &T::AccountId, to: &T::AccountId) {
+			for canonical in <RedirectsOf<T>>::get(from) {
+				<Redirects<T>>::insert(&canonical, to);
+			}
+			<RedirectsOf<T>>::swap(from, to);
+		}
+
+		/// Tell `OnNameChange` that `who` went from `old` to `new`, if they differ.
+		fn note_rename(who: &T::AccountId, old: &[u8], new: &[u8]) {
+			if old != new {
+				T::OnNameChange::on_name_changed(who, old, new);
+			}
+		}
+
+		/// Whether `who`'s profile is reflected in [`LengthHistogram`] and [`ProfileHashes`]. An
+		/// unfinished `force_rebuild_indexes` run has only counted the accounts up to
+		/// [`RebuildCursor`]; the rest are counted when the run reaches them, so changes to their
+		/// profiles must not touch the indexes until then.
+		fn is_indexed(who: &T::AccountId) -> bool {
+			match <RebuildCursor<T>>::get() {
+				None => true,
+				Some(RebuildStage::Clearing) | Some(RebuildStage::Counting(None)) => false,
+				Some(RebuildStage::Counting(Some(last))) => {
+					<AccountToUserInfo<T>>::hashed_key_for(who)
+						<= <AccountToUserInfo<T>>::hashed_key_for(last)
+				},
+			}
+		}
+
+		/// Move `who`'s name from the `old` length bucket to the `new` one. `None` stands for no
+		/// name, i.e. a profile being created or removed.
+		pub(crate) fn note_name_length(who: &T::AccountId, old: Option<usize>, new: Option<usize>) {
+			if old == new || !Self::is_indexed(who) {
+				return;
+			}
+			if let Some(old) = old {
+				<LengthHistogram<T>>::mutate_exists(old as u32, |count| {
+					*count =
+						count.and_then(|count| count.checked_sub(1)).filter(|count| *count > 0);
+				});
+			}
+			if let Some(new) = new {
+				<LengthHistogram<T>>::mutate(new as u32, |count| *count = count.saturating_add(1));
+			}
+		}
+
+		/// The [`ProfileHashes`] key for `user`'s name, age and title.
+		pub(crate) fn profile_hash(user: &User<T>) -> T::Hash {
+			T::Hashing::hash_of(&(&user.name, user.age, &user.title))
+		}
+
+		/// Move `who` from the `old` [`ProfileHashes`] entry to the `new` one. An entry held by
+		/// another account is left to that account.
+		pub(crate) fn note_profile_hash(
+			who: &T::AccountId,
+			old: Option<T::Hash>,
+			new: Option<T::Hash>,
+		) {
+			if old == new || !Self::is_indexed(who) {
+				return;
+			}
+			if let Some(old) = old {
+				<ProfileHashes<T>>::mutate_exists(old, |holder| {
+					if holder.as_ref() == Some(who) {
+						*holder = None;
+					}
+				});
+			}
+			if let Some(new) = new {
+				<ProfileHashes<T>>::mutate(new, |holder| {
+					holder.get_or_insert_with(|| who.clone());
+				});
+			}
+		}
+
+		fn ensure_not_duplicate(who: &T::AccountId, hash: &T::Hash) -> DispatchResult {
+			ensure!(
+				!T::RejectDuplicateProfiles::get()
+					|| <ProfileHashes<T>>::get(hash).map_or(true, |holder| holder == *who),
+				Error::<T>::DuplicateProfile
+			);
+			Ok(())
+		}
+
+		/// Call `f` for up to `limit` items, where `f` handles one item and returns the weight it
+		/// used, at most `heaviest`, or `None` once there are no items left. Stops early when one
+		/// more item could take the total past `AdminWeightBudget`; the first item always runs
+		/// so repeated calls make progress. Returns the number of items handled and their weight.
+		fn process_bounded<F>(limit: u32, heaviest: Weight, mut f: F) -> (u32, Weight)
+		where
+			F: FnMut() -> Option<Weight>,
+		{
+			let budget = T::AdminWeightBudget::get();
+			let mut done = 0;
+			let mut used = Weight::zero();
+			while done < limit {
+				if done > 0 && used.saturating_add(heaviest).any_gt(budget) {
+					break;
+				}
+				let Some(weight) = f() else { break };
+				done += 1;
+				used.saturating_accrue(weight);
+			}
+			(done, used)
+		}
+
+		/// The weight of handling one item in a bounded admin loop.
+		fn item_weight(reads: u64, writes: u64) -> Weight {
+			Weight::from_parts(10_000, 0)
+				.saturating_add(T::DbWeight::get().reads_writes(reads, writes))
+		}
+
+		/// The declared weight of a bounded admin loop over up to `limit` items of at most
+		/// `heaviest` each: the call base plus whichever is smaller of the full loop and the
+		/// most `process_bounded` lets it run past `AdminWeightBudget`.
+		pub(crate) fn admin_weight(limit: u32, heaviest: Weight) -> Weight {
+			let budget = T::AdminWeightBudget::get().max(heaviest);
+			Weight::from_parts(10_000, 0)
+				.saturating_add(heaviest.saturating_mul(limit as u64).min(budget))
+		}
+
+		/// The heaviest item of `sanitize_names`: a profile padded and its indexes moved.
+		fn sanitize_item_weight() -> Weight {
+			Self::item_weight(2, 3)
+		}
+
+		/// The heaviest item of `force_rebuild_indexes`: a profile counted into both indexes.
+		fn rebuild_item_weight() -> Weight {
+			Self::item_weight(3, 2)
+		}
+
+		/// The post-dispatch info of a free admin call whose loop used `used`.
+		fn admin_post_info(used: Weight) -> PostDispatchInfo {
+			PostDispatchInfo {
+				actual_weight: Some(Weight::from_parts(10_000, 0).saturating_add(used)),
+				pays_fee: Pays::No,
+			}
+		}
+
+		/// `who`'s profile, unless it is private and `viewer` is not `who`.
+		fn readable(who: &T::AccountId, viewer: Option<&T::AccountId>) -> Option<User<T>> {
+			<AccountToUserInfo<T>>::get(who)
+				.filter(|user| user.visibility == Visibility::Public || viewer == Some(who))
+		}
+
+		/// The key `alias` is stored under in [`Redirects`]: its ASCII lowercase form.
+		fn canonical_alias(alias: &BoundedVec<u8, T::MaxLength>) -> BoundedVec<u8, T::MaxLength> {
+			let mut canonical = alias.clone();
+			canonical.as_mut().make_ascii_lowercase();
+			canonical
+		}
+
+		/// `user` with its age zeroed if the owner hid it.
+		fn public_view(mut user: User<T>) -> User<T> {
+			if user.age_hidden {
+				user.age = 0;
+			}
+			user
+		}
+
+		fn set_locked(who: &T::AccountId, locked: bool) -> DispatchResult {
+			<AccountToUserInfo<T>>::try_mutate(who, |maybe_user| -> DispatchResult {
+				let user = maybe_user.as_mut().ok_or(Error::<T>::UserNotAdded)?;
+				user.locked = locked;
+				Ok(())
+			})
+		}
+
+		fn ensure_title_allowed(
+			age: u8,
+			title: &BoundedVec<u8, T::MaxTitleLength>,
+		) -> DispatchResult {
+			ensure!(
+				age >= T::AdultAge::get() || !T::AdultOnlyTitles::get().contains(&title.as_slice()),
+				Error::<T>::TitleNotAllowedForAge
+			);
+			ensure!(
+				<TitleMinAge<T>>::get(title).map_or(true, |min_age| age >= min_age),
+				Error::<T>::TitleAgeRequirement
+			);
+			Ok(())
+		}
+
+		fn ensure_name_allowed(name: &[u8]) -> DispatchResult {
+			let limits = Self::runtime_config();
+			ensure!(name.len() as u32 <= limits.max_length, Error::<T>::TooLong);
+			ensure!(name.len() as u32 >= limits.min_length, Error::<T>::TooShort);
+			ensure!(
+				!T::RejectAddressLikeNames::get() || !Self::looks_like_address(name),
+				Error::<T>::NameLooksLikeAddress
+			);
+			Ok(())
+		}
+
+		/// Whether `name` is an account id in hex, with or without `0x`, or base58 that decodes
+		/// to the length of an SS58 address: a one or two byte prefix, the account id and a
+		/// two byte checksum. The checksum itself is not verified.
+		fn looks_like_address(name: &[u8]) -> bool {
+			let account_len = T::AccountId::max_encoded_len();
+			let hex = name.strip_prefix(b"0x").unwrap_or(name);
+			if hex.len() == 2 * account_len && hex.iter().all(u8::is_ascii_hexdigit) {
+				return true;
+			}
+			match Self::decode_base58(name).as_deref() {
+				Some([first, rest @ ..]) => {
+					let prefix_len = match first {
+						0..=63 => 1,
+						64..=127 => 2,
+						_ => return false,
+					};
+					rest.len() + 1 == prefix_len + account_len + 2
+				},
+				_ => false,
+			}
+		}
+
+		/// `input` decoded from the Bitcoin base58 alphabet that SS58 uses, or `None` if it is
+		/// empty or contains any other byte.
+		fn decode_base58(input: &[u8]) -> Option<Vec<u8>> {
+			const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+			if input.is_empty() {
+				return None;
+			}
+			// Big-endian base-256 digits of the value read so far.
+			let mut bytes = Vec::<u8>::new();
+			for c in input {
+				let mut carry = ALPHABET.iter().position(|a| a == c)? as u32;
+				for byte in bytes.iter_mut().rev() {
+					carry += *byte as u32 * 58;
+					*byte = carry as u8;
+					carry >>= 8;
+				}
+				while carry > 0 {
+					bytes.insert(0, carry as u8);
+					carry >>= 8;
+				}
+			}
+			// Each leading '1' stands for a leading zero byte.
+			let zeros = input.iter().take_while(|c| **c == b'1').count();
+			let mut decoded = Vec::with_capacity(zeros + bytes.len());
+			decoded.resize(zeros, 0);
+			decoded.extend(bytes);
+			Some(decoded)
+		}
+
+		/// Whether `value` is in the format `claim_type` requires. The checks are deliberately
+		/// shallow: they keep obvious junk out rather than fully validate.
+		fn claim_well_formed(claim_type: ClaimType, value: &[u8]) -> bool {
+			if value.iter().any(|b| !b.is_ascii_graphic()) {
+				return false;
+			}
+			match claim_type {
+				ClaimType::Email => {
+					let mut parts = value.split(|b| *b == b'@');
+					match (parts.next(), parts.next(), parts.next()) {
+						(Some(local), Some(domain), None) => {
+							!local.is_empty()
+								&& domain.split(|b| *b == b'.').count() > 1
+								&& domain.split(|b| *b == b'.').all(|label| !label.is_empty())
+						},
+						_ => false,
+					}
+				},
+				ClaimType::Website => {
+					value.len() > b"https://".len() && value.starts_with(b"https://")
+				},
+			}
+		}
+
+		fn has_padding(name: &[u8]) -> bool {
+			matches!(name.last(), Some(b) if *b == 0 || b.is_ascii_whitespace())
+		}
+	}
+
+	impl<T: Config> crate::traits::ProfileRegistrar<T::AccountId> for Pallet<T> {
+		fn ensure_profile(
+			who: &T::AccountId,
+			name: Vec<u8>,
+			age: u8,
+			title: Vec<u8>,
+		) -> DispatchResult {
+			if <AccountToUserInfo<T>>::contains_key(who) {
+				return Ok(());
+			}
+			Self::do_insert(who, name, age, title)
+		}
 	}
 }
@@ -0,0 +1,77 @@
+//! Storage migrations for this pallet.
+
+use crate::*;
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::TryRuntimeError;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+#[cfg(feature = "try-runtime")]
+use scale_info::prelude::vec::Vec;
+
+pub mod v1 {
+	use super::*;
+
+	/// A profile as stored before storage version 1: a name, an age and a title bounded by
+	/// `MaxLength`.
+	#[derive(Decode)]
+	pub struct OldUser<T: Config> {
+		pub name: BoundedVec<u8, T::MaxLength>,
+		pub age: u8,
+		pub title: BoundedVec<u8, T::MaxLength>,
+	}
+
+	/// Re-encodes every stored profile in the current [`User`] layout. Flags, locks and a
+	/// pronoun start unset; the profile is public with its age shown and stays discoverable.
+	///
+	/// [`LengthHistogram`] and [`ProfileHashes`] did not exist before this version, so they are
+	/// seeded in the same pass.
+	pub struct MigrateToV1<T>(core::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 0 {
+				return T::DbWeight::get().reads(1);
+			}
+			let mut translated = 0u64;
+			<AccountToUserInfo<T>>::translate::<OldUser<T>, _>(|who, old| {
+				translated += 1;
+				let user = User {
+					name: old.name,
+					age: old.age,
+					// `MaxTitleLength` is at least `MaxLength`, so nothing is cut.
+					title: BoundedVec::truncate_from(old.title.into_inner()),
+					..Default::default()
+				};
+				Pallet::<T>::note_name_length(&who, None, Some(user.name.len()));
+				Pallet::<T>::note_profile_hash(&who, None, Some(Pallet::<T>::profile_hash(&user)));
+				Some(user)
+			});
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(3 * translated + 1, 3 * translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((<AccountToUserInfo<T>>::iter_keys().count() as u64).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let before = u64::decode(&mut &state[..])
+				.map_err(|_| TryRuntimeError::Other("invalid pre-upgrade state"))?;
+			ensure!(
+				StorageVersion::get::<Pallet<T>>() == 1,
+				TryRuntimeError::Other("storage version not updated")
+			);
+			// `iter` drops values that fail to decode, so this also checks the new layout.
+			ensure!(
+				<AccountToUserInfo<T>>::iter().count() as u64 == before,
+				TryRuntimeError::Other("profiles lost in migration")
+			);
+			ensure!(
+				Pallet::<T>::stats().user_count as u64 == before,
+				TryRuntimeError::Other("length histogram not seeded")
+			);
+			Ok(())
+		}
+	}
+}
@@ -1,16 +1,37 @@
 use crate as pallet_template;
 use frame_support::{
-	derive_impl,
-	traits::{ConstU16, ConstU64},
+	derive_impl, parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, ConstU8},
+	weights::Weight,
 };
 use sp_core::H256;
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, LookupError, StaticLookup},
 	BuildStorage,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
+/// An address that [`TestLookup`] refuses to resolve.
+pub const UNRESOLVABLE: u64 = u64::MAX;
+
+/// Identity lookup, except that [`UNRESOLVABLE`] fails to resolve.
+pub struct TestLookup;
+impl StaticLookup for TestLookup {
+	type Source = u64;
+	type Target = u64;
+	fn lookup(s: u64) -> Result<u64, LookupError> {
+		if s == UNRESOLVABLE {
+			Err(LookupError)
+		} else {
+			Ok(s)
+		}
+	}
+	fn unlookup(t: u64) -> u64 {
+		t
+	}
+}
+
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
 	pub enum Test
@@ -32,7 +53,7 @@ impl frame_system::Config for Test {
 	type Hash = H256;
 	type Hashing = BlakeTwo256;
 	type AccountId = u64;
-	type Lookup = IdentityLookup<Self::AccountId>;
+	type Lookup = TestLookup;
 	type Block = Block;
 	type RuntimeEvent = RuntimeEvent;
 	type BlockHashCount = ConstU64<250>;
@@ -47,9 +68,47 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+	pub const DefaultTitle: &'static [u8] = b"member";
+	pub AdultOnlyTitles: Vec<&'static [u8]> = vec![b"sommelier"];
+	pub static RejectAddressLikeNames: bool = true;
+	pub static RejectDuplicateProfiles: bool = false;
+	pub static AutoTitleFromAge: bool = false;
+	pub static AdminWeightBudget: Weight = Weight::MAX;
+	pub static NameChanges: Vec<(u64, Vec<u8>, Vec<u8>)> = Vec::new();
+}
+
 impl pallet_template::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
+	type MaxLength = ConstU32<16>;
+	type MaxTitleLength = ConstU32<24>;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type DefaultTitle = DefaultTitle;
+	type AdultAge = ConstU8<18>;
+	type AdultOnlyTitles = AdultOnlyTitles;
+	type MinCommitDelay = ConstU64<3>;
+	type TransferOfferTimeout = ConstU64<10>;
+	type MaxBatch = ConstU32<4>;
+	type RejectAddressLikeNames = RejectAddressLikeNames;
+	type RejectDuplicateProfiles = RejectDuplicateProfiles;
+	type AutoTitleFromAge = AutoTitleFromAge;
+	type MaxRedirects = ConstU32<2>;
+	type MaxRegistrationsPerBlock = ConstU32<8>;
+	type MaxClaims = ConstU32<1>;
+	type ClaimLen = ConstU32<32>;
+	type HeartbeatInterval = ConstU64<5>;
+	type AdminWeightBudget = AdminWeightBudget;
+	type OnNameChange = RecordNameChanges;
+}
+
+/// Records every rename in [`NameChanges`].
+pub struct RecordNameChanges;
+
+impl pallet_template::traits::NameChangeHandler<u64> for RecordNameChanges {
+	fn on_name_changed(who: &u64, old: &[u8], new: &[u8]) {
+		NameChanges::mutate(|changes| changes.push((*who, old.to_vec(), new.to_vec())));
+	}
 }
 
 // Build genesis storage according to the mock runtime.
@@ -0,0 +1,85 @@
+//! Runtime API for reading nickname data without submitting an extrinsic.
+
+use crate::{NickConfig, NickStats, ProfileStatus, RuntimeConfig};
+use codec::Codec;
+use scale_info::prelude::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait NicksApi<AccountId, User, Hash>
+	where
+		AccountId: Codec,
+		User: Codec,
+		Hash: Codec,
+	{
+		/// The pallet's configured limits.
+		fn config_values() -> NickConfig;
+
+		/// The profile registered by `account`, if any, with a hidden age read as `0`.
+		fn whoami(account: AccountId) -> Option<User>;
+
+		/// The profile registered by `account`, or an empty one.
+		fn whoami_or_default(account: AccountId) -> User;
+
+		/// `account`'s profile as stored, SCALE-encoded for `import_profile`. Only returned when
+		/// `viewer` is `account`.
+		fn export_profile(account: AccountId, viewer: Option<AccountId>) -> Option<Vec<u8>>;
+
+		/// Like `whoami`, but a private profile is returned when `viewer` is its owner.
+		fn whoami_as(account: AccountId, viewer: Option<AccountId>) -> Option<User>;
+
+		/// Whether `account` is registered as younger than `AdultAge`. `None` if the age is
+		/// hidden.
+		fn is_minor(account: AccountId) -> Option<bool>;
+
+		/// The encoded size in bytes of `account`'s stored profile, if it is public.
+		fn encoded_len_of(account: AccountId) -> Option<u32>;
+
+		/// Up to `limit` titles with their holder counts.
+		fn title_counts(limit: u32) -> Vec<(Vec<u8>, u32)>;
+
+		/// `bytes` cut to at most `max` bytes on a UTF-8 character boundary.
+		fn safe_truncate(bytes: Vec<u8>, max: u32) -> Vec<u8>;
+
+		/// A hash of `account`'s public profile that changes whenever it does.
+		fn profile_digest(account: AccountId) -> Option<Hash>;
+
+		/// The profiles of up to `MaxBatch` accounts, in input order.
+		fn users_batch(accounts: Vec<AccountId>) -> Vec<Option<User>>;
+
+		/// Public names starting with `prefix` among the next `limit` profiles after the
+		/// `start` cursor, and the cursor for the page after.
+		fn names_with_prefix_paged(
+			prefix: Vec<u8>,
+			start: Option<Vec<u8>>,
+			limit: u32,
+		) -> (Vec<(Vec<u8>, AccountId)>, Option<Vec<u8>>);
+
+		/// How many stored names have each length, as `(length, count)`.
+		fn length_histogram() -> Vec<(u32, u32)>;
+
+		/// The state of `account`'s public profile, if any.
+		fn status_of(account: AccountId) -> Option<ProfileStatus>;
+
+		/// Pallet-wide counters.
+		fn stats() -> NickStats;
+
+		/// `account`'s name and the chain's SS58 prefix.
+		fn user_with_address(account: AccountId) -> Option<(Vec<u8>, u16)>;
+
+		/// The account `alias` redirects to.
+		fn resolve(alias: Vec<u8>) -> Option<AccountId>;
+
+		/// `alias` in the casing it was added with.
+		fn display_alias(alias: Vec<u8>) -> Option<Vec<u8>>;
+
+		/// Whether `account` passes the length, lock, allowlist and rate limit checks of
+		/// registering now, or the index of the error it would get.
+		fn can_register(account: AccountId, name_len: u32, title_len: u32) -> Result<(), u8>;
+
+		/// The name length limits in force.
+		fn runtime_config() -> RuntimeConfig;
+
+		/// Whether `account` sent a heartbeat within the last `window` blocks.
+		fn is_active(account: AccountId, window: u32) -> bool;
+	}
+}
@@ -1,27 +1,2681 @@
-use crate::{mock::*, Error, Event, Something};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	mock::*, traits::ProfileRegistrar, AccountToUserInfo, AllowedRegistrants, ClaimType, Claims,
+	Commitments, DisplayByCanonical, EditRequest, Error, Event, LastSeen, LengthHistogram,
+	NickConfig, NickStats, ProfileHashes, ProfileStatus, ProfileUpdate, Pronoun, RebuildCursor,
+	RebuildStage, Redirects, RedirectsOf, RegistrationsThisBlock, RuntimeConfig, SanitizeCursor,
+	Seq, SubOwner, TransferOffers, Visibility,
+};
+use codec::Encode;
+use frame_support::traits::{GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{GetDispatchInfo, Pays},
+	weights::Weight,
+};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
 
 #[test]
-fn it_works_for_default_value() {
+fn insert_user_info_works() {
 	new_test_ext().execute_with(|| {
 		// Go past genesis block so events get deposited
 		System::set_block_number(1);
-		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(Something::<Test>::get(), Some(42));
-		// Assert that the correct event was deposited
-		System::assert_last_event(Event::SomethingStored { something: 42, who: 1 }.into());
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"alice".to_vec());
+		assert_eq!(user.age, 30);
+		assert_eq!(user.title.to_vec(), b"dev".to_vec());
+		System::assert_last_event(Event::UserInfoAdded(1, 1).into());
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn insert_user_info_rejects_long_name() {
 	new_test_ext().execute_with(|| {
-		// Ensure the expected error is thrown when no value is present.
 		assert_noop!(
-			TemplateModule::cause_error(RuntimeOrigin::signed(1)),
-			Error::<Test>::NoneValue
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(1),
+				vec![b'a'; 17],
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::TooLong
+		);
+	});
+}
+
+#[test]
+fn update_profile_changes_one_field_at_a_time() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"alicia".to_vec()), ..Default::default() }
+		));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"alicia".to_vec());
+		assert_eq!(user.age, 30);
+		assert_eq!(user.title.to_vec(), b"dev".to_vec());
+		System::assert_last_event(Event::UserInfoUpdated(1, 2).into());
+
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { age: Some(31), ..Default::default() }
+		));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"alicia".to_vec());
+		assert_eq!(user.age, 31);
+
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { title: Some(b"lead".to_vec()), ..Default::default() }
+		));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.age, 31);
+		assert_eq!(user.title.to_vec(), b"lead".to_vec());
+	});
+}
+
+#[test]
+fn update_profile_requires_existing_profile() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { age: Some(31), ..Default::default() }
+			),
+			Error::<Test>::UserNotAdded
+		);
+	});
+}
+
+#[test]
+fn update_profile_rejects_long_title() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { title: Some(vec![b'a'; 25]), ..Default::default() }
+			),
+			Error::<Test>::TooLong
+		);
+	});
+}
+
+#[test]
+fn sanitize_names_trims_padding() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice\0\0".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob \t".to_vec(),
+			40,
+			b"ops".to_vec()
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(3),
+			b"carol".to_vec(),
+			50,
+			b"qa".to_vec()
+		));
+
+		assert_ok!(TemplateModule::sanitize_names(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::NamesSanitized(2).into());
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().name.to_vec(), b"alice".to_vec());
+		assert_eq!(AccountToUserInfo::<Test>::get(2).unwrap().name.to_vec(), b"bob".to_vec());
+		assert_eq!(AccountToUserInfo::<Test>::get(3).unwrap().name.to_vec(), b"carol".to_vec());
+
+		// Running it again finds nothing left to trim.
+		assert_ok!(TemplateModule::sanitize_names(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::NamesSanitized(0).into());
+	});
+}
+
+#[test]
+fn sanitize_names_resumes_where_it_stopped() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		for (who, name) in [(1, &b"alice "[..]), (2, b"bob "), (3, b"carol ")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+
+		assert_ok!(TemplateModule::sanitize_names(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::NamesSanitized(2).into());
+		assert!(SanitizeCursor::<Test>::get().is_some());
+		assert_ok!(TemplateModule::sanitize_names(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::NamesSanitized(1).into());
+		assert_eq!(SanitizeCursor::<Test>::get(), None);
+		for who in 1..=3 {
+			assert!(!AccountToUserInfo::<Test>::get(who).unwrap().name.ends_with(b" "));
+		}
+	});
+}
+
+#[test]
+fn sanitize_names_requires_force_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::sanitize_names(RuntimeOrigin::signed(1), 10),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn profile_events_are_indexed_by_account() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { age: Some(31), ..Default::default() }
+		));
+
+		let topic = BlakeTwo256::hash_of(&1u64);
+		assert_eq!(TemplateModule::account_topic(&1), topic);
+		let events = System::events();
+		assert_eq!(events.len(), 2);
+		for record in events {
+			assert_eq!(record.topics, vec![topic, BlakeTwo256::hash(b"alice")]);
+		}
+		assert_eq!(System::event_topics(&topic), vec![(1, 0), (1, 1)]);
+	});
+}
+
+#[test]
+fn omitted_title_gets_default() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			Vec::new()
+		));
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().title.to_vec(), b"member".to_vec());
+	});
+}
+
+#[test]
+fn provided_title_overrides_default() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().title.to_vec(), b"dev".to_vec());
+	});
+}
+
+#[test]
+fn config_values_match_mock_constants() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			TemplateModule::config_values(),
+			NickConfig {
+				max_length: 16,
+				max_title_length: 24,
+				adult_age: 18,
+				min_commit_delay: 3,
+				transfer_offer_timeout: 10,
+				max_redirects: 2,
+				max_registrations_per_block: 8,
+				max_claims: 1,
+				claim_len: 32,
+				heartbeat_interval: 5,
+				admin_weight_budget: Weight::MAX,
+				max_batch: 4,
+			}
+		);
+	});
+}
+
+#[test]
+fn clear_title_keeps_name_and_age() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::clear_title(RuntimeOrigin::signed(1)));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"alice".to_vec());
+		assert_eq!(user.age, 30);
+		assert!(user.title.is_empty());
+		System::assert_last_event(Event::UserInfoUpdated(1, 2).into());
+	});
+}
+
+#[test]
+fn clear_title_requires_existing_profile() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::clear_title(RuntimeOrigin::signed(1)),
+			Error::<Test>::UserNotAdded
+		);
+	});
+}
+
+#[test]
+fn insert_user_info_overwrites_existing_profile() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alicia".to_vec(),
+			31,
+			b"lead".to_vec()
+		));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"alicia".to_vec());
+		assert_eq!(user.age, 31);
+		assert_eq!(user.title.to_vec(), b"lead".to_vec());
+		System::assert_last_event(Event::UserInfoAdded(1, 2).into());
+	});
+}
+
+#[test]
+fn failed_insert_leaves_existing_profile_untouched() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(1),
+				b"alicia".to_vec(),
+				31,
+				vec![b'a'; 25]
+			),
+			Error::<Test>::TooLong
+		);
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().name.to_vec(), b"alice".to_vec());
+	});
+}
+
+#[test]
+fn force_set_name_overrides_name() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::force_set_name(RuntimeOrigin::root(), 1, b"fixed".to_vec()));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"fixed".to_vec());
+		assert_eq!(user.age, 30);
+		System::assert_last_event(Event::UserInfoForced(1).into());
+	});
+}
+
+#[test]
+fn force_set_name_checks_origin_and_profile() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::force_set_name(RuntimeOrigin::signed(2), 1, b"fixed".to_vec()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			TemplateModule::force_set_name(RuntimeOrigin::root(), 1, b"fixed".to_vec()),
+			Error::<Test>::UserNotAdded
+		);
+	});
+}
+
+#[test]
+fn whoami_matches_storage() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::whoami(&1), None);
+		assert_eq!(TemplateModule::whoami_or_default(&1), Default::default());
+		assert!(TemplateModule::whoami_or_default(&1).discoverable);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_eq!(TemplateModule::whoami(&1), AccountToUserInfo::<Test>::get(1));
+		assert_eq!(
+			TemplateModule::whoami_or_default(&1),
+			AccountToUserInfo::<Test>::get(1).unwrap()
+		);
+	});
+}
+
+#[test]
+fn is_minor_follows_adult_age_boundary() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::is_minor(&1), None);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"kid".to_vec(),
+			17,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"grown".to_vec(),
+			18,
+			b"dev".to_vec()
+		));
+		assert_eq!(TemplateModule::is_minor(&1), Some(true));
+		assert_eq!(TemplateModule::is_minor(&2), Some(false));
+	});
+}
+
+#[test]
+fn adult_only_title_is_rejected_for_minors() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(1),
+				b"kid".to_vec(),
+				17,
+				b"sommelier".to_vec()
+			),
+			Error::<Test>::TitleNotAllowedForAge
+		);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"grown".to_vec(),
+			18,
+			b"sommelier".to_vec()
+		));
+		// Lowering the age below the boundary while keeping the title is rejected too.
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(2),
+				ProfileUpdate { age: Some(17), ..Default::default() }
+			),
+			Error::<Test>::TitleNotAllowedForAge
+		);
+	});
+}
+
+#[test]
+fn force_set_name_rejects_unresolvable_target() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::force_set_name(RuntimeOrigin::root(), UNRESOLVABLE, b"fixed".to_vec()),
+			Error::<Test>::InvalidRecipient
+		);
+	});
+}
+
+#[test]
+fn encoded_len_of_matches_manual_encoding() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::encoded_len_of(&1), None);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		let manual = (
+			b"alice".to_vec(),
+			30u8,
+			b"dev".to_vec(),
+			false,
+			Vec::<u8>::new(),
+			false,
+			false,
+			None::<u8>,
+			Visibility::Public,
+			true,
+		)
+			.encode();
+		assert_eq!(TemplateModule::encoded_len_of(&1), Some(manual.len() as u32));
+	});
+}
+
+#[test]
+fn force_merge_profiles_keeps_primary() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"alice2".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 1, 2));
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().name.to_vec(), b"alice".to_vec());
+		assert!(!AccountToUserInfo::<Test>::contains_key(2));
+		System::assert_last_event(Event::ProfilesMerged(1, 2).into());
+	});
+}
+
+#[test]
+fn force_merge_profiles_moves_secondary_to_empty_primary() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 1, 2));
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().name.to_vec(), b"alice".to_vec());
+		assert!(!AccountToUserInfo::<Test>::contains_key(2));
+	});
+}
+
+#[test]
+fn force_merge_profiles_rejects_bad_input() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 1, 1),
+			Error::<Test>::SameAccount
+		);
+		assert_noop!(
+			TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 1, 2),
+			Error::<Test>::UserNotAdded
+		);
+		assert_noop!(
+			TemplateModule::force_merge_profiles(RuntimeOrigin::signed(1), 1, 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn title_counts_groups_shared_titles() {
+	new_test_ext().execute_with(|| {
+		for (who, title) in [(1, &b"dev"[..]), (2, b"ops"), (3, b"dev"), (4, b"qa"), (5, b"dev")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				b"user".to_vec(),
+				30,
+				title.to_vec()
+			));
+		}
+		assert_eq!(
+			TemplateModule::title_counts(10),
+			vec![(b"dev".to_vec(), 3), (b"ops".to_vec(), 1), (b"qa".to_vec(), 1)]
+		);
+		assert_eq!(TemplateModule::title_counts(1), vec![(b"dev".to_vec(), 3)]);
+	});
+}
+
+#[test]
+fn safe_truncate_respects_utf8_boundaries() {
+	let name = "ab😀é".as_bytes();
+	assert_eq!(name.len(), 8);
+	assert_eq!(TemplateModule::safe_truncate(name, 8), name.to_vec());
+	assert_eq!(TemplateModule::safe_truncate(name, 20), name.to_vec());
+	// Cutting inside the two-byte `é` drops it entirely.
+	assert_eq!(TemplateModule::safe_truncate(name, 7), "ab😀".as_bytes().to_vec());
+	assert_eq!(TemplateModule::safe_truncate(name, 6), "ab😀".as_bytes().to_vec());
+	// Cutting anywhere inside the four-byte emoji drops it entirely.
+	for max in 2..6 {
+		assert_eq!(TemplateModule::safe_truncate(name, max), b"ab".to_vec());
+	}
+	assert_eq!(TemplateModule::safe_truncate(name, 0), Vec::<u8>::new());
+}
+
+#[test]
+fn flag_and_unflag_profile() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::flag_profile(
+			RuntimeOrigin::root(),
+			1,
+			b"impersonation".to_vec()
+		));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert!(user.flagged);
+		assert_eq!(user.flag_reason.to_vec(), b"impersonation".to_vec());
+		System::assert_last_event(Event::ProfileFlagged(1).into());
+
+		assert_ok!(TemplateModule::unflag_profile(RuntimeOrigin::root(), 1));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert!(!user.flagged);
+		assert!(user.flag_reason.is_empty());
+		System::assert_last_event(Event::ProfileUnflagged(1).into());
+	});
+}
+
+#[test]
+fn flagging_requires_force_origin_and_profile() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::flag_profile(RuntimeOrigin::root(), 1, b"spam".to_vec()),
+			Error::<Test>::UserNotAdded
+		);
+		assert_noop!(
+			TemplateModule::flag_profile(RuntimeOrigin::signed(2), 1, b"spam".to_vec()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			TemplateModule::unflag_profile(RuntimeOrigin::signed(2), 1),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn flagged_profile_cannot_be_renamed_by_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::flag_profile(RuntimeOrigin::root(), 1, b"spam".to_vec()));
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { name: Some(b"bob".to_vec()), ..Default::default() }
+			),
+			Error::<Test>::ProfileFlagged
+		);
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(1),
+				b"bob".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::ProfileFlagged
+		);
+		// Other fields can still change, and re-registering keeps the flag.
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			31,
+			b"ops".to_vec()
+		));
+		assert!(AccountToUserInfo::<Test>::get(1).unwrap().flagged);
+
+		assert_ok!(TemplateModule::unflag_profile(RuntimeOrigin::root(), 1));
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"bob".to_vec()), ..Default::default() }
+		));
+	});
+}
+
+fn commitment(name: &[u8], salt: &[u8]) -> H256 {
+	BlakeTwo256::hash_of(&(name.to_vec(), salt.to_vec()))
+}
+
+#[test]
+fn commit_then_reveal_registers_name() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::commit_name(
+			RuntimeOrigin::signed(1),
+			commitment(b"alice", b"salt")
+		));
+		System::assert_last_event(Event::NameCommitted(1).into());
+
+		System::set_block_number(4);
+		assert_ok!(TemplateModule::reveal_name(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			b"salt".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().name.to_vec(), b"alice".to_vec());
+		assert!(!Commitments::<Test>::contains_key(1));
+		System::assert_last_event(Event::UserInfoAdded(1, 1).into());
+	});
+}
+
+#[test]
+fn reveal_with_wrong_salt_fails() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::commit_name(
+			RuntimeOrigin::signed(1),
+			commitment(b"alice", b"salt")
+		));
+		System::set_block_number(4);
+		assert_noop!(
+			TemplateModule::reveal_name(
+				RuntimeOrigin::signed(1),
+				b"alice".to_vec(),
+				b"pepper".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::CommitmentMismatch
+		);
+	});
+}
+
+#[test]
+fn reveal_too_early_or_without_commitment_fails() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::reveal_name(
+				RuntimeOrigin::signed(1),
+				b"alice".to_vec(),
+				b"salt".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::NoCommitment
+		);
+		assert_ok!(TemplateModule::commit_name(
+			RuntimeOrigin::signed(1),
+			commitment(b"alice", b"salt")
+		));
+		System::set_block_number(3);
+		assert_noop!(
+			TemplateModule::reveal_name(
+				RuntimeOrigin::signed(1),
+				b"alice".to_vec(),
+				b"salt".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::RevealTooEarly
+		);
+	});
+}
+
+#[test]
+fn find_account_by_scan_matches_exact_names() {
+	new_test_ext().execute_with(|| {
+		for (who, name) in [(1, &b"alice"[..]), (2, b"bob"), (3, b"carol")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		assert_eq!(TemplateModule::find_account_by_scan(b"bob", 10), Some(2));
+		assert_eq!(TemplateModule::find_account_by_scan(b"bo", 10), None);
+		assert_eq!(TemplateModule::find_account_by_scan(b"dave", 10), None);
+		// A limit of zero scans nothing.
+		assert_eq!(TemplateModule::find_account_by_scan(b"bob", 0), None);
+	});
+}
+
+#[test]
+fn privileged_calls_do_not_pay_fees() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		let results = [
+			TemplateModule::sanitize_names(RuntimeOrigin::root(), 10),
+			TemplateModule::force_set_name(RuntimeOrigin::root(), 1, b"fixed".to_vec()),
+			TemplateModule::flag_profile(RuntimeOrigin::root(), 1, b"spam".to_vec()),
+			TemplateModule::unflag_profile(RuntimeOrigin::root(), 1),
+			TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 1, 2),
+		];
+		for result in results {
+			assert_eq!(result.unwrap().pays_fee, Pays::No);
+		}
+	});
+}
+
+#[test]
+fn profile_digest_changes_on_edit() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::profile_digest(&1), None);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		let before = TemplateModule::profile_digest(&1).unwrap();
+		assert_eq!(before, BlakeTwo256::hash_of(&AccountToUserInfo::<Test>::get(1).unwrap()));
+
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { age: Some(31), ..Default::default() }
+		));
+		let after = TemplateModule::profile_digest(&1).unwrap();
+		assert_ne!(before, after);
+
+		// Writing the same values back restores the original digest.
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { age: Some(30), ..Default::default() }
+		));
+		assert_eq!(TemplateModule::profile_digest(&1), Some(before));
+	});
+}
+
+#[test]
+fn locked_profile_blocks_self_edits() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		System::assert_last_event(Event::ProfileLocked(1).into());
+
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(1),
+				b"alicia".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::ProfileLocked
+		);
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { age: Some(31), ..Default::default() }
+			),
+			Error::<Test>::ProfileLocked
+		);
+		assert_noop!(
+			TemplateModule::clear_title(RuntimeOrigin::signed(1)),
+			Error::<Test>::ProfileLocked
+		);
+
+		assert_ok!(TemplateModule::unlock_profile(RuntimeOrigin::signed(1)));
+		System::assert_last_event(Event::ProfileUnlocked(1).into());
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { age: Some(31), ..Default::default() }
+		));
+	});
+}
+
+#[test]
+fn locked_profile_still_accepts_force_edits() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		assert_ok!(TemplateModule::force_set_name(RuntimeOrigin::root(), 1, b"fixed".to_vec()));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"fixed".to_vec());
+		assert!(user.locked);
+	});
+}
+
+#[test]
+fn locking_requires_profile() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::lock_profile(RuntimeOrigin::signed(1)),
+			Error::<Test>::UserNotAdded
+		);
+	});
+}
+
+#[test]
+fn users_batch_preserves_order_and_bound() {
+	new_test_ext().execute_with(|| {
+		for (who, name) in [(1, &b"alice"[..]), (3, b"carol")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		let names: Vec<Option<Vec<u8>>> = TemplateModule::users_batch(vec![3, 2, 1, 4, 1])
+			.into_iter()
+			.map(|user| user.map(|user| user.name.to_vec()))
+			.collect();
+		// Only the first `MaxBatch` (4) accounts are looked up.
+		assert_eq!(names, vec![Some(b"carol".to_vec()), None, Some(b"alice".to_vec()), None]);
+	});
+}
+
+#[test]
+fn hidden_age_is_masked_but_still_checked() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::set_age_visibility(RuntimeOrigin::signed(1), true),
+			Error::<Test>::UserNotAdded
+		);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			12,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::set_age_visibility(RuntimeOrigin::signed(1), true));
+		System::assert_last_event(Event::AgeVisibilitySet(1, true).into());
+
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().age, 12);
+		assert_eq!(TemplateModule::whoami(&1).unwrap().age, 0);
+		assert_eq!(TemplateModule::users_batch(vec![1])[0].as_ref().unwrap().age, 0);
+		assert_eq!(TemplateModule::is_minor(&1), None);
+		// The stored age still keeps the adults-only title out of reach.
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { title: Some(b"sommelier".to_vec()), ..Default::default() }
+			),
+			Error::<Test>::TitleNotAllowedForAge
+		);
+
+		assert_ok!(TemplateModule::set_age_visibility(RuntimeOrigin::signed(1), false));
+		assert_eq!(TemplateModule::whoami(&1).unwrap().age, 12);
+		assert_eq!(TemplateModule::is_minor(&1), Some(true));
+	});
+}
+
+#[test]
+fn length_histogram_tracks_renames() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::length_histogram(), vec![]);
+		for (who, name) in [(1, &b"alice"[..]), (2, b"bob"), (3, b"carol")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		assert_eq!(TemplateModule::length_histogram(), vec![(3, 1), (5, 2)]);
+
+		// Renaming to a name of the same length leaves the buckets alone.
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"alina".to_vec()), ..Default::default() }
+		));
+		assert_eq!(TemplateModule::length_histogram(), vec![(3, 1), (5, 2)]);
+
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"robert".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert!(!LengthHistogram::<Test>::contains_key(3));
+		assert_eq!(TemplateModule::length_histogram(), vec![(5, 2), (6, 1)]);
+
+		assert_ok!(TemplateModule::force_set_name(RuntimeOrigin::root(), 3, b"c".to_vec()));
+		assert_eq!(TemplateModule::length_histogram(), vec![(1, 1), (5, 1), (6, 1)]);
+
+		// Merging drops the secondary name when the primary keeps its own.
+		assert_ok!(TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 1, 2));
+		assert_eq!(TemplateModule::length_histogram(), vec![(1, 1), (5, 1)]);
+
+		// A failed edit leaves the buckets untouched.
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(3),
+				ProfileUpdate {
+					name: Some(b"carol".to_vec()),
+					title: Some(b"sommelier".to_vec()),
+					age: Some(12),
+				}
+			),
+			Error::<Test>::TitleNotAllowedForAge
+		);
+		assert_eq!(TemplateModule::length_histogram(), vec![(1, 1), (5, 1)]);
+	});
+}
+
+#[test]
+fn ensure_profile_is_idempotent() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(<TemplateModule as ProfileRegistrar<u64>>::ensure_profile(
+			&1,
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		let first = AccountToUserInfo::<Test>::get(1);
+		assert_eq!(first.as_ref().unwrap().name.to_vec(), b"alice".to_vec());
+		assert_ok!(<TemplateModule as ProfileRegistrar<u64>>::ensure_profile(
+			&1,
+			b"bob".to_vec(),
+			40,
+			Vec::new()
+		));
+		assert_eq!(AccountToUserInfo::<Test>::get(1), first);
+	});
+}
+
+#[test]
+fn failed_ensure_profile_leaves_indexes_alone() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		RejectDuplicateProfiles::set(true);
+		let histogram = TemplateModule::length_histogram();
+		// Called directly, as another pallet would, with no storage layer to roll back.
+		assert_eq!(
+			<TemplateModule as ProfileRegistrar<u64>>::ensure_profile(
+				&2,
+				b"alice".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Err(Error::<Test>::DuplicateProfile.into())
+		);
+		assert_eq!(AccountToUserInfo::<Test>::get(2), None);
+		assert_eq!(TemplateModule::length_histogram(), histogram);
+		assert_eq!(TemplateModule::stats().user_count, 1);
+	});
+}
+
+#[test]
+fn title_min_age_is_enforced() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::set_title_min_age(
+				RuntimeOrigin::signed(1),
+				b"senior".to_vec(),
+				Some(65)
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(TemplateModule::set_title_min_age(
+			RuntimeOrigin::root(),
+			b"senior".to_vec(),
+			Some(65)
+		));
+		System::assert_last_event(
+			Event::TitleMinAgeSet(b"senior".to_vec().try_into().unwrap(), Some(65)).into(),
+		);
+
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(1),
+				b"alice".to_vec(),
+				64,
+				b"senior".to_vec()
+			),
+			Error::<Test>::TitleAgeRequirement
+		);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			65,
+			b"senior".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { age: Some(40), ..Default::default() }
+			),
+			Error::<Test>::TitleAgeRequirement
+		);
+
+		assert_ok!(TemplateModule::set_title_min_age(
+			RuntimeOrigin::root(),
+			b"senior".to_vec(),
+			None
+		));
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { age: Some(40), ..Default::default() }
+		));
+	});
+}
+
+#[test]
+fn status_of_reflects_flags() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::status_of(&1), None);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_eq!(TemplateModule::status_of(&1), Some(ProfileStatus::Active));
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::status_of(&1), Some(ProfileStatus::Locked));
+		assert_ok!(TemplateModule::flag_profile(RuntimeOrigin::root(), 1, b"spam".to_vec()));
+		assert_eq!(TemplateModule::status_of(&1), Some(ProfileStatus::Flagged));
+		assert_ok!(TemplateModule::unlock_profile(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::status_of(&1), Some(ProfileStatus::Flagged));
+		assert_ok!(TemplateModule::unflag_profile(RuntimeOrigin::root(), 1));
+		assert_eq!(TemplateModule::status_of(&1), Some(ProfileStatus::Active));
+	});
+}
+
+#[test]
+fn titles_may_exceed_max_length_up_to_max_title_length() {
+	new_test_ext().execute_with(|| {
+		let title = b"principal engineer".to_vec();
+		assert!(title.len() > 16 && title.len() <= 24);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			title.clone()
+		));
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().title.to_vec(), title);
+		// Names are still held to `MaxLength`.
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { name: Some(title), ..Default::default() }
+			),
+			Error::<Test>::TooLong
+		);
+	});
+}
+
+#[test]
+fn force_rebuild_indexes_recounts_in_chunks() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		for (who, name) in [(1, &b"alice"[..]), (2, b"bob"), (3, b"carol")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		let expected = TemplateModule::length_histogram();
+		LengthHistogram::<Test>::insert(3, 7);
+		LengthHistogram::<Test>::insert(9, 1);
+		LengthHistogram::<Test>::remove(5);
+
+		assert_noop!(
+			TemplateModule::force_rebuild_indexes(RuntimeOrigin::signed(1), 10),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		// Two histogram buckets and three hashes to remove before any profile is counted.
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 4));
+		System::assert_last_event(Event::IndexesRebuilt(0, false).into());
+		assert_eq!(RebuildCursor::<Test>::get(), Some(RebuildStage::Clearing));
+		assert_eq!(ProfileHashes::<Test>::iter().count(), 1);
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::IndexesRebuilt(1, false).into());
+		assert!(matches!(RebuildCursor::<Test>::get(), Some(RebuildStage::Counting(Some(_)))));
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::IndexesRebuilt(2, true).into());
+		assert_eq!(RebuildCursor::<Test>::get(), None);
+		assert_eq!(TemplateModule::length_histogram(), expected);
+		assert_eq!(ProfileHashes::<Test>::iter().count(), 3);
+
+		// A finished rebuild starts over from scratch.
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::IndexesRebuilt(3, true).into());
+		assert_eq!(TemplateModule::length_histogram(), expected);
+	});
+}
+
+#[test]
+fn address_like_names_are_rejected_when_configured() {
+	new_test_ext().execute_with(|| {
+		// A `u64` account id in hex, and an SS58 encoding of one with prefix 42.
+		for name in [&b"0102030405060708"[..], b"BR8AUemT3J8Sb7o"] {
+			assert_noop!(
+				TemplateModule::insert_user_info(
+					RuntimeOrigin::signed(1),
+					name.to_vec(),
+					30,
+					b"dev".to_vec()
+				),
+				Error::<Test>::NameLooksLikeAddress
+			);
+		}
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { name: Some(b"BR8AUemT3J8Sb7o".to_vec()), ..Default::default() }
+			),
+			Error::<Test>::NameLooksLikeAddress
+		);
+		// Base58 of the wrong length is just a name.
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"Bob".to_vec()), ..Default::default() }
+		));
+
+		RejectAddressLikeNames::set(false);
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"BR8AUemT3J8Sb7o".to_vec()), ..Default::default() }
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"0102030405060708".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+	});
+}
+
+#[test]
+fn stats_count_stored_profiles() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::stats(), NickStats { user_count: 0 });
+		for (who, name) in [(1, &b"alice"[..]), (2, b"bob"), (3, b"carol")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		assert_ok!(TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 1, 2));
+		assert_eq!(
+			TemplateModule::stats().user_count,
+			AccountToUserInfo::<Test>::iter_keys().count() as u32
+		);
+		assert_eq!(TemplateModule::stats(), NickStats { user_count: 2 });
+	});
+}
+
+#[test]
+fn duplicate_profiles_are_rejected_when_configured() {
+	new_test_ext().execute_with(|| {
+		let insert = |who: u64, name: &[u8], age: u8| {
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				age,
+				b"dev".to_vec(),
+			)
+		};
+		// With the check off, the index still records the first holder.
+		assert_ok!(insert(1, b"alice", 30));
+		assert_ok!(insert(2, b"alice", 30));
+		assert_eq!(ProfileHashes::<Test>::iter_values().collect::<Vec<_>>(), vec![1]);
+		assert_ok!(insert(2, b"bob", 30));
+
+		RejectDuplicateProfiles::set(true);
+		assert_noop!(insert(3, b"alice", 30), Error::<Test>::DuplicateProfile);
+		assert_ok!(insert(3, b"alice", 31));
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(3),
+				ProfileUpdate { age: Some(30), ..Default::default() }
+			),
+			Error::<Test>::DuplicateProfile
+		);
+		// Re-submitting one's own profile is not a duplicate.
+		assert_ok!(insert(1, b"alice", 30));
+
+		// Once the holder moves on, the triple is free again.
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"alina".to_vec()), ..Default::default() }
+		));
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(3),
+			ProfileUpdate { age: Some(30), ..Default::default() }
+		));
+		assert_noop!(insert(4, b"alina", 30), Error::<Test>::DuplicateProfile);
+	});
+}
+
+#[test]
+fn user_with_address_uses_system_ss58_prefix() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::user_with_address(&1), None);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_eq!(TemplateModule::user_with_address(&1), Some((b"alice".to_vec(), 42)));
+	});
+}
+
+fn insert_alice() {
+	assert_ok!(TemplateModule::insert_user_info(
+		RuntimeOrigin::signed(1),
+		b"alice".to_vec(),
+		30,
+		b"dev".to_vec()
+	));
+}
+
+#[test]
+fn accepted_transfer_moves_profile() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		let profile = AccountToUserInfo::<Test>::get(1);
+		assert_noop!(
+			TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1),
+			Error::<Test>::NoTransferOffer
+		);
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		System::assert_last_event(Event::TransferOffered(1, 2).into());
+		assert_noop!(
+			TemplateModule::accept_transfer(RuntimeOrigin::signed(3), 1),
+			Error::<Test>::NoTransferOffer
+		);
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1));
+		System::assert_last_event(Event::TransferAccepted(1, 2).into());
+		assert_eq!(AccountToUserInfo::<Test>::get(1), None);
+		assert_eq!(AccountToUserInfo::<Test>::get(2), profile);
+		assert_eq!(TransferOffers::<Test>::get(1), None);
+		assert_eq!(ProfileHashes::<Test>::iter_values().collect::<Vec<_>>(), vec![2]);
+	});
+}
+
+#[test]
+fn accepting_a_transfer_counts_as_registering() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		assert_ok!(TemplateModule::set_registrant_allowed(RuntimeOrigin::root(), 3, true));
+		assert_noop!(
+			TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1),
+			Error::<Test>::NotAllowed
+		);
+		assert_ok!(TemplateModule::set_registrant_allowed(RuntimeOrigin::root(), 3, false));
+
+		RegistrationsThisBlock::<Test>::put(8);
+		assert_noop!(
+			TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1),
+			Error::<Test>::RateLimited
+		);
+		RegistrationsThisBlock::<Test>::kill();
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1));
+		assert_eq!(RegistrationsThisBlock::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn transferred_sub_profiles_leave_their_parent() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::register_sub(
+			RuntimeOrigin::signed(1),
+			2,
+			b"alice-bot".to_vec(),
+			1,
+			b"bot".to_vec()
+		));
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(2), 3));
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(3), 2));
+		assert_eq!(SubOwner::<Test>::get(2), None);
+		assert_eq!(SubOwner::<Test>::get(3), None);
+		assert_noop!(
+			TemplateModule::clear_sub(RuntimeOrigin::signed(1), 2),
+			Error::<Test>::NotSubOwner
+		);
+	});
+}
+
+#[test]
+fn cancelled_transfer_cannot_be_accepted() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		assert_noop!(
+			TemplateModule::cancel_transfer(RuntimeOrigin::signed(1), 3),
+			Error::<Test>::NoTransferOffer
+		);
+		assert_ok!(TemplateModule::cancel_transfer(RuntimeOrigin::signed(1), 2));
+		System::assert_last_event(Event::TransferCancelled(1, 2).into());
+		assert_noop!(
+			TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1),
+			Error::<Test>::NoTransferOffer
+		);
+	});
+}
+
+#[test]
+fn transfer_offer_expires() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		System::set_block_number(12);
+		assert_noop!(
+			TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1),
+			Error::<Test>::TransferOfferExpired
+		);
+		// Renewing the offer restarts the clock.
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		System::set_block_number(22);
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1));
+	});
+}
+
+#[test]
+fn transfer_requires_recipient_without_profile() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 1),
+			Error::<Test>::SameAccount
+		);
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		assert_noop!(
+			TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1),
+			Error::<Test>::RecipientHasProfile
+		);
+	});
+}
+
+#[test]
+fn seq_increments_per_account_and_survives_transfers() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		System::assert_last_event(Event::UserInfoAdded(1, 1).into());
+		assert_ok!(TemplateModule::clear_title(RuntimeOrigin::signed(1)));
+		System::assert_last_event(Event::UserInfoUpdated(1, 2).into());
+		assert_ok!(TemplateModule::update_profile(RuntimeOrigin::signed(1), Default::default()));
+		System::assert_last_event(Event::UserInfoUpdated(1, 3).into());
+		// Failed edits do not use up a number.
+		assert_noop!(
+			TemplateModule::update_profile(
+				RuntimeOrigin::signed(1),
+				ProfileUpdate { name: Some(vec![b'a'; 17]), ..Default::default() }
+			),
+			Error::<Test>::TooLong
+		);
+		assert_eq!(Seq::<Test>::get(1), 3);
+
+		// Other accounts count on their own.
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		System::assert_last_event(Event::UserInfoAdded(2, 1).into());
+
+		// Giving the profile away keeps the account's count, which carries on if it
+		// registers again.
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 3));
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(3), 1));
+		assert_eq!(Seq::<Test>::get(1), 3);
+		insert_alice();
+		System::assert_last_event(Event::UserInfoAdded(1, 4).into());
+	});
+}
+
+#[test]
+fn parent_registers_and_clears_sub_profile() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::register_sub(
+			RuntimeOrigin::signed(1),
+			2,
+			b"alice-bot".to_vec(),
+			1,
+			b"bot".to_vec()
+		));
+		System::assert_last_event(Event::SubRegistered(1, 2).into());
+		assert_eq!(AccountToUserInfo::<Test>::get(2).unwrap().name.to_vec(), b"alice-bot".to_vec());
+		assert_eq!(SubOwner::<Test>::get(2), Some(1));
+		assert_eq!(TemplateModule::stats().user_count, 1);
+
+		assert_noop!(
+			TemplateModule::register_sub(
+				RuntimeOrigin::signed(3),
+				2,
+				b"mallory".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::RecipientHasProfile
+		);
+		assert_noop!(
+			TemplateModule::clear_sub(RuntimeOrigin::signed(3), 2),
+			Error::<Test>::NotSubOwner
+		);
+
+		assert_ok!(TemplateModule::clear_sub(RuntimeOrigin::signed(1), 2));
+		System::assert_last_event(Event::SubCleared(1, 2).into());
+		assert_eq!(AccountToUserInfo::<Test>::get(2), None);
+		assert_eq!(SubOwner::<Test>::get(2), None);
+		assert_eq!(TemplateModule::length_histogram(), vec![]);
+		assert_eq!(ProfileHashes::<Test>::iter().count(), 0);
+
+		// A sub with no profile left has nothing to clear.
+		SubOwner::<Test>::insert(2, 1);
+		assert_noop!(
+			TemplateModule::clear_sub(RuntimeOrigin::signed(1), 2),
+			Error::<Test>::UserNotAdded
+		);
+	});
+}
+
+#[test]
+fn profile_events_are_indexed_by_name_hash() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let last_topics = || System::events().last().unwrap().topics.clone();
+		insert_alice();
+		let alice = BlakeTwo256::hash(b"alice");
+		assert_eq!(TemplateModule::name_topic(b"alice"), alice);
+		assert_eq!(last_topics(), vec![TemplateModule::account_topic(&1), alice]);
+
+		// Renames are indexed under the new name.
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"alina".to_vec()), ..Default::default() }
+		));
+		let alina = BlakeTwo256::hash(b"alina");
+		assert_eq!(last_topics(), vec![TemplateModule::account_topic(&1), alina]);
+
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		assert_eq!(last_topics(), vec![TemplateModule::account_topic(&2), alina]);
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1));
+		assert_eq!(last_topics(), vec![TemplateModule::account_topic(&2), alina]);
+	});
+}
+
+#[test]
+fn clean_dead_removes_profiles_of_reaped_accounts() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		System::inc_providers(&1);
+		assert_noop!(
+			TemplateModule::clean_dead(RuntimeOrigin::signed(3), 1),
+			Error::<Test>::AccountStillAlive
+		);
+
+		let _ = System::dec_providers(&1);
+		assert_ok!(TemplateModule::clean_dead(RuntimeOrigin::signed(3), 1));
+		System::assert_last_event(Event::DeadProfileCleaned(1).into());
+		assert_eq!(AccountToUserInfo::<Test>::get(1), None);
+		assert_eq!(TransferOffers::<Test>::get(1), None);
+		assert_eq!(TemplateModule::stats().user_count, 0);
+		assert_eq!(ProfileHashes::<Test>::iter().count(), 0);
+		assert_noop!(
+			TemplateModule::clean_dead(RuntimeOrigin::signed(3), 1),
+			Error::<Test>::UserNotAdded
+		);
+	});
+}
+
+#[test]
+fn clean_dead_leaves_sub_profiles_to_their_parent() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::register_sub(
+			RuntimeOrigin::signed(1),
+			2,
+			b"alice-bot".to_vec(),
+			1,
+			b"bot".to_vec()
+		));
+		assert!(!System::account_exists(&2));
+		assert_noop!(
+			TemplateModule::clean_dead(RuntimeOrigin::signed(9), 2),
+			Error::<Test>::SubProfile
+		);
+		assert_ok!(TemplateModule::clear_sub(RuntimeOrigin::signed(1), 2));
+	});
+}
+
+#[test]
+fn can_register_reports_first_blocking_error() {
+	new_test_ext().execute_with(|| {
+		let code = |error: Error<Test>| error.encode()[0];
+		assert_eq!(TemplateModule::can_register(&1, 16, 24), Ok(()));
+		assert_eq!(TemplateModule::can_register(&1, 17, 3), Err(code(Error::TooLong)));
+		assert_eq!(TemplateModule::can_register(&1, 5, 25), Err(code(Error::TooLong)));
+
+		insert_alice();
+		assert_eq!(TemplateModule::can_register(&1, 5, 3), Ok(()));
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::can_register(&1, 5, 3), Err(code(Error::ProfileLocked)));
+		RegistrationsThisBlock::<Test>::put(8);
+		assert_eq!(TemplateModule::can_register(&2, 5, 3), Err(code(Error::RateLimited)));
+		RegistrationsThisBlock::<Test>::kill();
+		// The code matches the error the call itself fails with.
+		let err = TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alice".to_vec(),
+			30,
+			b"dev".to_vec(),
+		)
+		.unwrap_err();
+		match err {
+			sp_runtime::DispatchError::Module(error) => {
+				assert_eq!(error.error[0], code(Error::ProfileLocked))
+			},
+			_ => panic!("unexpected error {:?}", err),
+		}
+	});
+}
+
+#[test]
+fn set_pronoun_sets_and_clears() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::set_pronoun(RuntimeOrigin::signed(1), Some(Pronoun::TheyThem)),
+			Error::<Test>::UserNotAdded
+		);
+		insert_alice();
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().pronoun, None);
+		assert_ok!(TemplateModule::set_pronoun(RuntimeOrigin::signed(1), Some(Pronoun::TheyThem)));
+		System::assert_last_event(Event::PronounSet(1, Some(Pronoun::TheyThem)).into());
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().pronoun, Some(Pronoun::TheyThem));
+		assert_ok!(TemplateModule::set_pronoun(RuntimeOrigin::signed(1), None));
+		assert_eq!(AccountToUserInfo::<Test>::get(1).unwrap().pronoun, None);
+	});
+}
+
+#[test]
+fn migration_to_v1_keeps_baseline_profiles_readable() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TemplateModule>();
+		let old = (b"alice".to_vec(), 30u8, b"dev".to_vec());
+		let key = AccountToUserInfo::<Test>::hashed_key_for(1);
+		frame_support::storage::unhashed::put(&key, &old);
+		assert_eq!(AccountToUserInfo::<Test>::get(1), None);
+
+		crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(user.name.to_vec(), b"alice".to_vec());
+		assert_eq!(user.age, 30);
+		assert_eq!(user.title.to_vec(), b"dev".to_vec());
+		assert!(!user.flagged);
+		assert!(user.flag_reason.is_empty());
+		assert!(!user.locked);
+		assert!(!user.age_hidden);
+		assert_eq!(user.pronoun, None);
+		assert_eq!(user.visibility, Visibility::Public);
+		assert!(user.discoverable);
+		assert_eq!(TemplateModule::on_chain_storage_version(), StorageVersion::new(1));
+		assert_eq!(TemplateModule::stats().user_count, 1);
+		assert_eq!(TemplateModule::length_histogram(), vec![(5, 1)]);
+		assert_eq!(ProfileHashes::<Test>::iter_values().collect::<Vec<_>>(), vec![1]);
+
+		// Seeded hashes make duplicate checks cover migrated profiles.
+		RejectDuplicateProfiles::set(true);
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(2),
+				b"alice".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::DuplicateProfile
+		);
+
+		// Running it again leaves migrated data alone.
+		let raw = frame_support::storage::unhashed::get_raw(&key);
+		crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(frame_support::storage::unhashed::get_raw(&key), raw);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn migration_to_v1_passes_its_upgrade_checks() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TemplateModule>();
+		for who in 1..=3u64 {
+			let old = (b"alice".to_vec(), 30u8, b"dev".to_vec());
+			frame_support::storage::unhashed::put(
+				&AccountToUserInfo::<Test>::hashed_key_for(who),
+				&old,
+			);
+		}
+		let state = crate::migrations::v1::MigrateToV1::<Test>::pre_upgrade().unwrap();
+		crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_ok!(crate::migrations::v1::MigrateToV1::<Test>::post_upgrade(state));
+	});
+}
+
+#[test]
+fn redirects_resolve_to_their_profile() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"ally".to_vec()),
+			Error::<Test>::UserNotAdded
+		);
+		insert_alice();
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"ally".to_vec()));
+		System::assert_last_event(
+			Event::RedirectAdded(1, b"ally".to_vec().try_into().unwrap()).into(),
+		);
+		assert_eq!(TemplateModule::resolve(b"ally"), Some(1));
+		assert_eq!(TemplateModule::resolve(b"alice"), None);
+
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::add_redirect(RuntimeOrigin::signed(2), b"ally".to_vec()),
+			Error::<Test>::AliasTaken
+		);
+		assert_noop!(
+			TemplateModule::remove_redirect(RuntimeOrigin::signed(2), b"ally".to_vec()),
+			Error::<Test>::NotAliasOwner
+		);
+
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"al".to_vec()));
+		assert_noop!(
+			TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"lis".to_vec()),
+			Error::<Test>::TooManyRedirects
+		);
+		assert_ok!(TemplateModule::remove_redirect(RuntimeOrigin::signed(1), b"ally".to_vec()));
+		assert_eq!(TemplateModule::resolve(b"ally"), None);
+		assert_eq!(Redirects::<Test>::iter_keys().count(), 1);
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"lis".to_vec()));
+	});
+}
+
+#[test]
+fn registrations_are_rate_limited_per_block() {
+	new_test_ext().execute_with(|| {
+		let register = |who: u64| {
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				b"alice".to_vec(),
+				30,
+				b"dev".to_vec(),
+			)
+		};
+		for who in 1..=8 {
+			assert_ok!(register(who));
+		}
+		assert_noop!(register(9), Error::<Test>::RateLimited);
+		// Other pallets and the force origin are not limited.
+		assert_ok!(<TemplateModule as ProfileRegistrar<u64>>::ensure_profile(
+			&9,
+			b"ivy".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::force_set_name(RuntimeOrigin::root(), 9, b"ivy2".to_vec()));
+
+		System::set_block_number(2);
+		TemplateModule::on_initialize(2);
+		assert_ok!(register(10));
+	});
+}
+
+#[test]
+fn claims_are_validated_per_type() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::add_claim(
+				RuntimeOrigin::signed(1),
+				ClaimType::Email,
+				b"a@b.io".to_vec()
+			),
+			Error::<Test>::UserNotAdded
+		);
+		insert_alice();
+		for malformed in
+			[&b"alice"[..], b"alice@", b"@example.com", b"a@b@c.io", b"a@example.", b"a @b.io"]
+		{
+			assert_noop!(
+				TemplateModule::add_claim(
+					RuntimeOrigin::signed(1),
+					ClaimType::Email,
+					malformed.to_vec()
+				),
+				Error::<Test>::MalformedClaim
+			);
+		}
+		assert_ok!(TemplateModule::add_claim(
+			RuntimeOrigin::signed(1),
+			ClaimType::Email,
+			b"alice@example.com".to_vec()
+		));
+		System::assert_last_event(Event::ClaimAdded(1, ClaimType::Email).into());
+		// A second claim of the same type replaces the first.
+		assert_ok!(TemplateModule::add_claim(
+			RuntimeOrigin::signed(1),
+			ClaimType::Email,
+			b"alice@example.org".to_vec()
+		));
+		assert_eq!(
+			Claims::<Test>::get(1).into_inner(),
+			vec![(ClaimType::Email, b"alice@example.org".to_vec().try_into().unwrap())]
+		);
+		assert_noop!(
+			TemplateModule::add_claim(
+				RuntimeOrigin::signed(1),
+				ClaimType::Website,
+				b"https://alice.dev".to_vec()
+			),
+			Error::<Test>::TooManyClaims
+		);
+		assert_noop!(
+			TemplateModule::remove_claim(RuntimeOrigin::signed(1), ClaimType::Website),
+			Error::<Test>::NoSuchClaim
+		);
+		assert_ok!(TemplateModule::remove_claim(RuntimeOrigin::signed(1), ClaimType::Email));
+		System::assert_last_event(Event::ClaimRemoved(1, ClaimType::Email).into());
+		assert!(!Claims::<Test>::contains_key(1));
+
+		assert_noop!(
+			TemplateModule::add_claim(
+				RuntimeOrigin::signed(1),
+				ClaimType::Website,
+				b"http://alice.dev".to_vec()
+			),
+			Error::<Test>::MalformedClaim
+		);
+		assert_ok!(TemplateModule::add_claim(
+			RuntimeOrigin::signed(1),
+			ClaimType::Website,
+			b"https://alice.dev".to_vec()
+		));
+
+		// Claims follow the profile when it is transferred.
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1));
+		assert!(!Claims::<Test>::contains_key(1));
+		assert_eq!(Claims::<Test>::get(2).len(), 1);
+	});
+}
+
+#[test]
+fn heartbeat_is_rate_limited_and_drives_is_active() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			TemplateModule::heartbeat(RuntimeOrigin::signed(1)),
+			Error::<Test>::UserNotAdded
+		);
+		insert_alice();
+		assert!(!TemplateModule::is_active(&1, 10));
+
+		assert_ok!(TemplateModule::heartbeat(RuntimeOrigin::signed(1)));
+		assert_eq!(LastSeen::<Test>::get(1), Some(1));
+		System::assert_last_event(Event::Heartbeat(1).into());
+		assert_noop!(
+			TemplateModule::heartbeat(RuntimeOrigin::signed(1)),
+			Error::<Test>::HeartbeatTooSoon
+		);
+
+		System::set_block_number(6);
+		assert!(TemplateModule::is_active(&1, 5));
+		assert!(!TemplateModule::is_active(&1, 4));
+		assert_ok!(TemplateModule::heartbeat(RuntimeOrigin::signed(1)));
+		assert_eq!(LastSeen::<Test>::get(1), Some(6));
+	});
+}
+
+#[test]
+fn private_profiles_are_redacted_but_still_count_as_duplicates() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		assert_ok!(TemplateModule::set_visibility(RuntimeOrigin::signed(1), Visibility::Private));
+		System::assert_last_event(Event::VisibilitySet(1, Visibility::Private).into());
+
+		assert_eq!(TemplateModule::whoami(&1), None);
+		assert_eq!(TemplateModule::whoami_as(&1, Some(&2)), None);
+		assert_eq!(
+			TemplateModule::whoami_as(&1, Some(&1)).unwrap().name.to_vec(),
+			b"alice".to_vec()
+		);
+		assert_eq!(TemplateModule::users_batch(vec![1]), vec![None]);
+		assert_eq!(TemplateModule::is_minor(&1), None);
+		assert_eq!(TemplateModule::user_with_address(&1), None);
+
+		let alice = AccountToUserInfo::<Test>::get(1).unwrap();
+		RejectDuplicateProfiles::set(true);
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(2),
+				alice.name.to_vec(),
+				alice.age,
+				alice.title.to_vec()
+			),
+			Error::<Test>::DuplicateProfile
+		);
+
+		assert_ok!(TemplateModule::set_visibility(RuntimeOrigin::signed(1), Visibility::Public));
+		assert_eq!(TemplateModule::whoami(&1).unwrap().name, alice.name);
+	});
+}
+
+#[test]
+fn runtime_config_overrides_length_limits_within_caps() {
+	new_test_ext().execute_with(|| {
+		let insert = |who, name: &[u8]| {
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec(),
+			)
+		};
+		let tight = RuntimeConfig { min_length: 3, max_length: 5 };
+		assert_noop!(
+			TemplateModule::set_runtime_config(RuntimeOrigin::signed(1), Some(tight)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			TemplateModule::set_runtime_config(
+				RuntimeOrigin::root(),
+				Some(RuntimeConfig { min_length: 0, max_length: 17 })
+			),
+			Error::<Test>::InvalidRuntimeConfig
+		);
+		assert_noop!(
+			TemplateModule::set_runtime_config(
+				RuntimeOrigin::root(),
+				Some(RuntimeConfig { min_length: 6, max_length: 5 })
+			),
+			Error::<Test>::InvalidRuntimeConfig
+		);
+
+		assert_ok!(insert(1, b"alexander"));
+		assert_ok!(TemplateModule::set_runtime_config(RuntimeOrigin::root(), Some(tight)));
+		assert_eq!(TemplateModule::runtime_config(), tight);
+		assert_noop!(insert(2, b"alexander"), Error::<Test>::TooLong);
+		assert_noop!(insert(2, b"al"), Error::<Test>::TooShort);
+		assert_eq!(TemplateModule::can_register(&2, 6, 3), Err(Error::<Test>::TooLong.encode()[0]));
+		assert_ok!(insert(2, b"alex"));
+
+		assert_ok!(TemplateModule::set_runtime_config(RuntimeOrigin::root(), None));
+		assert_eq!(TemplateModule::runtime_config().max_length, 16);
+		assert_ok!(insert(3, b"alexander"));
+	});
+}
+
+#[test]
+fn profiles_changed_mid_rebuild_are_counted_once() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let register = |who: u64, name: &[u8]| {
+			RegistrationsThisBlock::<Test>::kill();
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec(),
+			)
+		};
+		for who in 1..=6 {
+			assert_ok!(register(who, b"alice"));
+		}
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 1));
+		for who in 7..=17 {
+			assert_ok!(register(who, b"bob"));
+		}
+		for who in [1, 2, 3, 7, 8] {
+			assert_ok!(TemplateModule::update_profile(
+				RuntimeOrigin::signed(who),
+				ProfileUpdate { name: Some(b"carol".to_vec()), ..Default::default() }
+			));
+		}
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(4), 18));
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(18), 4));
+		assert_ok!(TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 19, 5));
+		assert_ok!(TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 9, 6));
+
+		while TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 1).is_ok()
+			&& !System::events()
+				.iter()
+				.any(|record| record.event == Event::<Test>::IndexesRebuilt(1, true).into())
+		{}
+		assert_eq!(TemplateModule::stats().user_count, 16);
+		assert_eq!(AccountToUserInfo::<Test>::iter().count(), 16);
+		let histogram = TemplateModule::length_histogram();
+		let hashes = ProfileHashes::<Test>::iter().count();
+
+		// A run over the settled profiles agrees.
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 100));
+		assert_eq!(TemplateModule::length_histogram(), histogram);
+		assert_eq!(ProfileHashes::<Test>::iter().count(), hashes);
+	});
+}
+
+#[test]
+fn clear_title_rejects_duplicate_profiles() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::clear_title(RuntimeOrigin::signed(1)));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"alice".to_vec(),
+			30,
+			b"ops".to_vec()
+		));
+		RejectDuplicateProfiles::set(true);
+		assert_noop!(
+			TemplateModule::clear_title(RuntimeOrigin::signed(2)),
+			Error::<Test>::DuplicateProfile
+		);
+	});
+}
+
+#[test]
+fn force_rebuild_indexes_refills_profile_hashes() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		// Profiles registered before the hash index existed have no entry.
+		let _ = ProfileHashes::<Test>::clear(u32::MAX, None);
+		ProfileHashes::<Test>::insert(H256::repeat_byte(1), 9);
+		RejectDuplicateProfiles::set(true);
+
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 10));
+		assert_eq!(ProfileHashes::<Test>::iter_values().collect::<Vec<_>>(), vec![1]);
+		assert_noop!(
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(2),
+				b"alice".to_vec(),
+				30,
+				b"dev".to_vec()
+			),
+			Error::<Test>::DuplicateProfile
+		);
+	});
+}
+
+#[test]
+fn admin_loops_stop_at_the_weight_budget() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		for (who, name) in [(1, &b"alice "[..]), (2, b"bob "), (3, b"carol ")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		// Each item costs 10_000 with the mock's free database, so two fit.
+		AdminWeightBudget::set(Weight::from_parts(25_000, 0));
+
+		// Five index entries to clear, then three profiles to count.
+		let info = TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 10).unwrap();
+		System::assert_last_event(Event::IndexesRebuilt(0, false).into());
+		assert_eq!(info.actual_weight, Some(Weight::from_parts(30_000, 0)));
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::IndexesRebuilt(0, false).into());
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::IndexesRebuilt(1, false).into());
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::IndexesRebuilt(2, true).into());
+
+		assert_ok!(TemplateModule::sanitize_names(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::NamesSanitized(2).into());
+		AdminWeightBudget::set(Weight::MAX);
+		assert_ok!(TemplateModule::sanitize_names(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::NamesSanitized(1).into());
+
+		// A budget smaller than one item still lets each call make progress.
+		AdminWeightBudget::set(Weight::zero());
+		assert_ok!(TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), 10));
+		System::assert_last_event(Event::IndexesRebuilt(0, false).into());
+		assert_eq!(
+			ProfileHashes::<Test>::iter().count() + LengthHistogram::<Test>::iter().count(),
+			4
+		);
+	});
+}
+
+#[test]
+fn admin_loops_declare_what_they_report() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let declared =
+			|call: crate::Call<Test>| RuntimeCall::TemplateModule(call).get_dispatch_info().weight;
+		for budget in [0, 25_000, 1_000_000, u64::MAX] {
+			AdminWeightBudget::set(Weight::from_parts(budget, u64::MAX));
+			// Fresh padded names so every round has the heaviest items to handle.
+			RegistrationsThisBlock::<Test>::kill();
+			SanitizeCursor::<Test>::kill();
+			for (who, name) in [(1, &b"alice "[..]), (2, b"bob "), (3, b"carol ")] {
+				assert_ok!(TemplateModule::insert_user_info(
+					RuntimeOrigin::signed(who),
+					name.to_vec(),
+					30,
+					b"dev".to_vec()
+				));
+			}
+
+			let limit = 4;
+			let info = TemplateModule::sanitize_names(RuntimeOrigin::root(), limit).unwrap();
+			assert!(info
+				.actual_weight
+				.unwrap()
+				.all_lte(declared(crate::Call::sanitize_names { limit })));
+			for _ in 0..3 {
+				let info =
+					TemplateModule::force_rebuild_indexes(RuntimeOrigin::root(), limit).unwrap();
+				assert!(info
+					.actual_weight
+					.unwrap()
+					.all_lte(declared(crate::Call::force_rebuild_indexes { limit })));
+			}
+		}
+	});
+}
+
+#[test]
+fn auto_title_follows_age_brackets() {
+	new_test_ext().execute_with(|| {
+		for (min_age, title) in [(0, &b"novice"[..]), (30, b"veteran"), (60, b"elder")] {
+			assert_ok!(TemplateModule::set_age_title_bracket(
+				RuntimeOrigin::root(),
+				min_age,
+				Some(title.to_vec())
+			));
+		}
+		assert_noop!(
+			TemplateModule::set_age_title_bracket(RuntimeOrigin::signed(1), 10, None),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		let insert = |who, age| {
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				b"alice".to_vec(),
+				age,
+				b"dev".to_vec(),
+			)
+		};
+		let title = |who| AccountToUserInfo::<Test>::get(who).unwrap().title.to_vec();
+
+		// Off by default: the given title is kept.
+		assert_ok!(insert(1, 45));
+		assert_eq!(title(1), b"dev".to_vec());
+
+		AutoTitleFromAge::set(true);
+		for (who, age, expected) in
+			[(2, 5, &b"novice"[..]), (3, 29, b"novice"), (4, 30, b"veteran"), (5, 80, b"elder")]
+		{
+			assert_ok!(insert(who, age));
+			assert_eq!(title(who), expected.to_vec());
+		}
+
+		// Ages below every bracket fall back to the default title.
+		assert_ok!(TemplateModule::set_age_title_bracket(RuntimeOrigin::root(), 0, None));
+		assert_ok!(insert(6, 10));
+		assert_eq!(title(6), b"member".to_vec());
+	});
+}
+
+#[test]
+fn edit_validates_without_writing_or_applies_all_fields() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		let before = AccountToUserInfo::<Test>::get(1).unwrap();
+		let changes = ProfileUpdate {
+			name: Some(b"alina".to_vec()),
+			age: Some(40),
+			title: Some(b"ops".to_vec()),
+		};
+
+		assert_ok!(TemplateModule::edit(
+			RuntimeOrigin::signed(1),
+			EditRequest { changes: changes.clone(), validate_only: true }
+		));
+		assert_eq!(AccountToUserInfo::<Test>::get(1), Some(before));
+		assert_eq!(LengthHistogram::<Test>::get(5), 1);
+		assert_noop!(
+			TemplateModule::edit(
+				RuntimeOrigin::signed(1),
+				EditRequest {
+					changes: ProfileUpdate { title: Some(vec![b'x'; 25]), ..changes.clone() },
+					validate_only: true,
+				}
+			),
+			Error::<Test>::TooLong
+		);
+		assert_noop!(
+			TemplateModule::edit(
+				RuntimeOrigin::signed(2),
+				EditRequest { changes: changes.clone(), validate_only: true }
+			),
+			Error::<Test>::UserNotAdded
+		);
+
+		assert_ok!(TemplateModule::edit(
+			RuntimeOrigin::signed(1),
+			EditRequest { changes, validate_only: false }
+		));
+		let user = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(
+			(user.name.to_vec(), user.age, user.title.to_vec()),
+			(b"alina".to_vec(), 40, b"ops".to_vec())
+		);
+		System::assert_last_event(Event::UserInfoUpdated(1, 2).into());
+	});
+}
+
+#[test]
+fn aliases_resolve_in_any_casing_and_keep_their_display_form() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"SatoshiN".to_vec()));
+		for casing in [&b"SatoshiN"[..], b"satoshin", b"SATOSHIN", b"sAtOsHiN"] {
+			assert_eq!(TemplateModule::resolve(casing), Some(1));
+			assert_eq!(TemplateModule::display_alias(casing), Some(b"SatoshiN".to_vec()));
+		}
+		assert_eq!(
+			DisplayByCanonical::<Test>::iter_keys()
+				.map(|key| key.to_vec())
+				.collect::<Vec<_>>(),
+			vec![b"satoshin".to_vec()]
+		);
+
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::add_redirect(RuntimeOrigin::signed(2), b"satoshiN".to_vec()),
+			Error::<Test>::AliasTaken
+		);
+
+		assert_ok!(TemplateModule::remove_redirect(RuntimeOrigin::signed(1), b"SATOSHIN".to_vec()));
+		System::assert_last_event(
+			Event::RedirectRemoved(1, b"SatoshiN".to_vec().try_into().unwrap()).into(),
+		);
+		assert_eq!(TemplateModule::resolve(b"satoshin"), None);
+		assert_eq!(TemplateModule::display_alias(b"satoshin"), None);
+	});
+}
+
+#[test]
+fn allowlist_restricts_registration_once_non_empty() {
+	new_test_ext().execute_with(|| {
+		let insert = |who| {
+			TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				b"alice".to_vec(),
+				30,
+				b"dev".to_vec(),
+			)
+		};
+		// Open while the set is empty.
+		assert_ok!(insert(1));
+
+		assert_noop!(
+			TemplateModule::set_registrant_allowed(RuntimeOrigin::signed(1), 2, true),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(TemplateModule::set_registrant_allowed(RuntimeOrigin::root(), 2, true));
+		assert_noop!(insert(3), Error::<Test>::NotAllowed);
+		assert_eq!(
+			TemplateModule::can_register(&3, 5, 3),
+			Err(Error::<Test>::NotAllowed.encode()[0])
+		);
+		assert_ok!(insert(2));
+
+		assert_ok!(TemplateModule::set_registrant_allowed(RuntimeOrigin::root(), 2, false));
+		assert_eq!(AllowedRegistrants::<Test>::count(), 0);
+		assert_ok!(insert(3));
+	});
+}
+
+#[test]
+fn renames_reach_the_name_change_handler() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		// Registering is not a rename, and neither is re-submitting the same name.
+		insert_alice();
+		assert_eq!(NameChanges::get(), vec![]);
+
+		assert_ok!(TemplateModule::update_profile(
+			RuntimeOrigin::signed(1),
+			ProfileUpdate { name: Some(b"alina".to_vec()), ..Default::default() }
+		));
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(1),
+			b"alinka ".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::sanitize_names(RuntimeOrigin::root(), 10));
+		assert_ok!(TemplateModule::force_set_name(RuntimeOrigin::root(), 1, b"al".to_vec()));
+		// Failed renames are not reported.
+		assert_noop!(
+			TemplateModule::force_set_name(RuntimeOrigin::root(), 2, b"bob".to_vec()),
+			Error::<Test>::UserNotAdded
+		);
+
+		assert_eq!(
+			NameChanges::get(),
+			vec![
+				(1, b"alice".to_vec(), b"alina".to_vec()),
+				(1, b"alina".to_vec(), b"alinka ".to_vec()),
+				(1, b"alinka ".to_vec(), b"alinka".to_vec()),
+				(1, b"alinka".to_vec(), b"al".to_vec()),
+			]
+		);
+	});
+}
+
+#[test]
+fn diagnostics_report_profile_and_index_counts() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		for (who, name) in [(1, &b"alice"[..]), (2, b"bob"), (3, b"carol")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		assert_noop!(
+			TemplateModule::force_emit_diagnostics(RuntimeOrigin::signed(1)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(TemplateModule::force_emit_diagnostics(RuntimeOrigin::root()));
+		System::assert_last_event(Event::Diagnostics(3, 3, true).into());
+
+		// A drifted index shows up as a mismatch.
+		LengthHistogram::<Test>::remove(3);
+		assert_ok!(TemplateModule::force_emit_diagnostics(RuntimeOrigin::root()));
+		System::assert_last_event(Event::Diagnostics(3, 2, true).into());
+
+		AdminWeightBudget::set(Weight::zero());
+		assert_ok!(TemplateModule::force_emit_diagnostics(RuntimeOrigin::root()));
+		System::assert_last_event(Event::Diagnostics(1, 2, false).into());
+
+		// The declared weight covers what the call reports, whatever the budget.
+		for budget in [0, 25_000, 1_000_000] {
+			AdminWeightBudget::set(Weight::from_parts(budget, 0));
+			let declared = RuntimeCall::TemplateModule(crate::Call::force_emit_diagnostics {})
+				.get_dispatch_info()
+				.weight;
+			let info = TemplateModule::force_emit_diagnostics(RuntimeOrigin::root()).unwrap();
+			assert!(info.actual_weight.unwrap().all_lte(declared));
+		}
+	});
+}
+
+#[test]
+fn redirects_follow_their_profile_and_go_with_it() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"Ally".to_vec()));
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"lis".to_vec()));
+
+		// A transfer takes the aliases along, including towards the cap.
+		assert_ok!(TemplateModule::offer_transfer(RuntimeOrigin::signed(1), 2));
+		assert_ok!(TemplateModule::accept_transfer(RuntimeOrigin::signed(2), 1));
+		assert_eq!(TemplateModule::resolve(b"ally"), Some(2));
+		assert_eq!(RedirectsOf::<Test>::get(1).len(), 0);
+		assert_noop!(
+			TemplateModule::add_redirect(RuntimeOrigin::signed(2), b"al".to_vec()),
+			Error::<Test>::TooManyRedirects
+		);
+
+		// Removing the profile frees every alias it held.
+		assert_ok!(TemplateModule::clean_dead(RuntimeOrigin::signed(3), 2));
+		assert_eq!(Redirects::<Test>::iter().count(), 0);
+		assert_eq!(DisplayByCanonical::<Test>::iter().count(), 0);
+		assert_eq!(RedirectsOf::<Test>::get(2).len(), 0);
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(3),
+			b"carol".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(3), b"ally".to_vec()));
+	});
+}
+
+#[test]
+fn prefix_search_pages_through_every_match() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=7u64 {
+			let name = if who == 4 {
+				b"bob".to_vec()
+			} else {
+				[&b"ali"[..], &[b'0' + who as u8]].concat()
+			};
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name,
+				30,
+				b"dev".to_vec()
+			));
+		}
+		assert_ok!(TemplateModule::set_visibility(RuntimeOrigin::signed(7), Visibility::Private));
+
+		let mut found = Vec::new();
+		let mut start = None;
+		let mut pages = 0;
+		loop {
+			let (matches, cursor) = TemplateModule::names_with_prefix_paged(b"ali", start, 2);
+			assert!(matches.len() <= 2);
+			found.extend(matches);
+			pages += 1;
+			match cursor {
+				Some(next) => start = Some(next),
+				None => break,
+			}
+		}
+		assert_eq!(pages, 4);
+		found.sort();
+		assert_eq!(
+			found,
+			(1..=6u64)
+				.filter(|who| *who != 4)
+				.map(|who| ([&b"ali"[..], &[b'0' + who as u8]].concat(), who))
+				.collect::<Vec<_>>()
+		);
+	});
+}
+
+#[test]
+fn empty_prefix_pages_keep_their_place() {
+	new_test_ext().execute_with(|| {
+		// An empty map has nothing left to page through.
+		assert_eq!(TemplateModule::names_with_prefix_paged(b"ali", None, 0), (vec![], None));
+		for (who, name) in [(1, &b"alice"[..]), (2, b"alina")] {
+			assert_ok!(TemplateModule::insert_user_info(
+				RuntimeOrigin::signed(who),
+				name.to_vec(),
+				30,
+				b"dev".to_vec()
+			));
+		}
+		let (matches, cursor) = TemplateModule::names_with_prefix_paged(b"ali", None, 0);
+		assert_eq!(matches, vec![]);
+		let (matches, cursor) = TemplateModule::names_with_prefix_paged(b"ali", cursor, 1);
+		assert_eq!(matches.len(), 1);
+		let (matches, next) = TemplateModule::names_with_prefix_paged(b"ali", cursor.clone(), 0);
+		assert_eq!((matches, &next), (vec![], &cursor));
+		let (matches, next) = TemplateModule::names_with_prefix_paged(b"ali", next, 1);
+		assert_eq!((matches.len(), next), (1, None));
+	});
+}
+
+#[test]
+fn recasing_an_owned_alias_only_changes_its_display() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"ally".to_vec()));
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"lis".to_vec()));
+
+		// At the cap, yet recasing is allowed and does not take another slot.
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"Ally".to_vec()));
+		System::assert_last_event(
+			Event::RedirectAdded(1, b"Ally".to_vec().try_into().unwrap()).into(),
+		);
+		assert_eq!(TemplateModule::display_alias(b"ally"), Some(b"Ally".to_vec()));
+		assert_eq!(TemplateModule::resolve(b"ALLY"), Some(1));
+		assert_eq!(RedirectsOf::<Test>::get(1).len(), 2);
+
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::add_redirect(RuntimeOrigin::signed(2), b"ALLY".to_vec()),
+			Error::<Test>::AliasTaken
+		);
+	});
+}
+
+#[test]
+fn exported_profiles_import_on_another_account() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::set_pronoun(RuntimeOrigin::signed(1), Some(Pronoun::SheHer)));
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::export_profile(&2, Some(&2)), None);
+		let blob = TemplateModule::export_profile(&1, Some(&1)).unwrap();
+
+		assert_ok!(TemplateModule::import_profile(RuntimeOrigin::signed(2), blob.clone()));
+		let imported = AccountToUserInfo::<Test>::get(2).unwrap();
+		let original = AccountToUserInfo::<Test>::get(1).unwrap();
+		assert_eq!(imported.name, original.name);
+		assert_eq!(imported.pronoun, Some(Pronoun::SheHer));
+		assert!(!imported.locked);
+
+		assert_noop!(
+			TemplateModule::import_profile(
+				RuntimeOrigin::signed(3),
+				blob[..blob.len() - 1].to_vec()
+			),
+			Error::<Test>::InvalidProfileBlob
+		);
+		assert_noop!(
+			TemplateModule::import_profile(RuntimeOrigin::signed(3), [&blob[..], &[0]].concat()),
+			Error::<Test>::InvalidProfileBlob
+		);
+		// A name longer than MaxLength does not decode.
+		let mut too_long = vec![17 << 2];
+		too_long.extend([b'a'; 17]);
+		too_long.extend(&blob[6..]);
+		assert_noop!(
+			TemplateModule::import_profile(RuntimeOrigin::signed(3), too_long),
+			Error::<Test>::InvalidProfileBlob
+		);
+	});
+}
+
+#[test]
+fn exported_profiles_keep_hidden_ages() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::set_age_visibility(RuntimeOrigin::signed(1), true));
+		assert_eq!(TemplateModule::whoami(&1).unwrap().age, 0);
+		assert_eq!(TemplateModule::export_profile(&1, None), None);
+		assert_eq!(TemplateModule::export_profile(&1, Some(&2)), None);
+		let blob = TemplateModule::export_profile(&1, Some(&1)).unwrap();
+
+		assert_ok!(TemplateModule::import_profile(RuntimeOrigin::signed(2), blob));
+		let imported = AccountToUserInfo::<Test>::get(2).unwrap();
+		assert_eq!(imported.age, 30);
+		assert!(imported.age_hidden);
+	});
+}
+
+#[test]
+fn private_profiles_can_be_exported() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::set_visibility(RuntimeOrigin::signed(1), Visibility::Private));
+		assert_eq!(TemplateModule::whoami(&1), None);
+		assert_eq!(TemplateModule::export_profile(&1, Some(&2)), None);
+		assert_eq!(TemplateModule::encoded_len_of(&1), None);
+		assert_eq!(TemplateModule::profile_digest(&1), None);
+		assert_eq!(TemplateModule::status_of(&1), None);
+		let blob = TemplateModule::export_profile(&1, Some(&1)).unwrap();
+
+		assert_ok!(TemplateModule::import_profile(RuntimeOrigin::signed(2), blob));
+		let imported = AccountToUserInfo::<Test>::get(2).unwrap();
+		assert_eq!(imported.name.to_vec(), b"alice".to_vec());
+		assert_eq!(imported.visibility, Visibility::Private);
+	});
+}
+
+#[test]
+fn undiscoverable_profiles_skip_search_but_still_resolve() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		insert_alice();
+		assert!(AccountToUserInfo::<Test>::get(1).unwrap().discoverable);
+		assert_ok!(TemplateModule::add_redirect(RuntimeOrigin::signed(1), b"ally".to_vec()));
+		assert_eq!(TemplateModule::names_with_prefix_paged(b"ali", None, 10).0.len(), 1);
+
+		assert_ok!(TemplateModule::set_discoverable(RuntimeOrigin::signed(1), false));
+		System::assert_last_event(Event::DiscoverableSet(1, false).into());
+		assert_eq!(TemplateModule::names_with_prefix_paged(b"ali", None, 10).0, vec![]);
+		assert_eq!(TemplateModule::resolve(b"ally"), Some(1));
+		assert_eq!(TemplateModule::find_account_by_scan(b"alice", 10), Some(1));
+		assert_eq!(TemplateModule::whoami(&1).unwrap().name.to_vec(), b"alice".to_vec());
+
+		// Editing the profile leaves the choice alone.
+		insert_alice();
+		assert!(!AccountToUserInfo::<Test>::get(1).unwrap().discoverable);
+	});
+}
+
+#[test]
+fn locked_profiles_keep_their_visibility() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		assert_noop!(
+			TemplateModule::set_visibility(RuntimeOrigin::signed(1), Visibility::Private),
+			Error::<Test>::ProfileLocked
+		);
+	});
+}
+
+#[test]
+fn locked_profiles_keep_their_age_visibility() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		assert_noop!(
+			TemplateModule::set_age_visibility(RuntimeOrigin::signed(1), true),
+			Error::<Test>::ProfileLocked
+		);
+	});
+}
+
+#[test]
+fn locked_profiles_keep_their_discoverability() {
+	new_test_ext().execute_with(|| {
+		insert_alice();
+		assert_ok!(TemplateModule::lock_profile(RuntimeOrigin::signed(1)));
+		assert_noop!(
+			TemplateModule::set_discoverable(RuntimeOrigin::signed(1), false),
+			Error::<Test>::ProfileLocked
+		);
+	});
+}
+
+#[test]
+fn merged_sub_profiles_leave_their_parent() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::register_sub(
+			RuntimeOrigin::signed(1),
+			2,
+			b"alice-bot".to_vec(),
+			1,
+			b"bot".to_vec()
+		));
+		assert_ok!(TemplateModule::heartbeat(RuntimeOrigin::signed(2)));
+		assert_ok!(TemplateModule::force_merge_profiles(RuntimeOrigin::root(), 3, 2));
+		assert_eq!(SubOwner::<Test>::get(2), None);
+		assert_eq!(LastSeen::<Test>::get(2), None);
+		assert_eq!(AccountToUserInfo::<Test>::get(3).unwrap().name.to_vec(), b"alice-bot".to_vec());
+
+		assert_ok!(TemplateModule::insert_user_info(
+			RuntimeOrigin::signed(2),
+			b"bob".to_vec(),
+			30,
+			b"dev".to_vec()
+		));
+		assert_noop!(
+			TemplateModule::clear_sub(RuntimeOrigin::signed(1), 2),
+			Error::<Test>::NotSubOwner
 		);
 	});
 }
@@ -0,0 +1,21 @@
+//! Interfaces other pallets can use to work with nickname profiles.
+
+use frame_support::pallet_prelude::DispatchResult;
+use scale_info::prelude::vec::Vec;
+
+/// Creates profiles on behalf of other pallets.
+pub trait ProfileRegistrar<AccountId> {
+	/// Register `name`, `age` and `title` for `who` unless they already have a profile, in
+	/// which case nothing changes.
+	fn ensure_profile(who: &AccountId, name: Vec<u8>, age: u8, title: Vec<u8>) -> DispatchResult;
+}
+
+/// Reacts to a profile's name changing, so other pallets can follow it.
+pub trait NameChangeHandler<AccountId> {
+	/// `who`'s profile was renamed from `old` to `new`.
+	fn on_name_changed(who: &AccountId, old: &[u8], new: &[u8]);
+}
+
+impl<AccountId> NameChangeHandler<AccountId> for () {
+	fn on_name_changed(_: &AccountId, _: &[u8], _: &[u8]) {}
+}
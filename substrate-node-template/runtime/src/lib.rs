@@ -245,11 +245,35 @@ impl pallet_sudo::Config for Runtime {
 	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const DefaultTitle: &'static [u8] = b"member";
+	pub AdultOnlyTitles: Vec<&'static [u8]> = Vec::new();
+	pub AdminWeightBudget: Weight = Perbill::from_percent(25) * BlockWeights::get().max_block;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
 	type MaxLength = ConstU32<50>;
+	type MaxTitleLength = ConstU32<64>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type DefaultTitle = DefaultTitle;
+	type AdultAge = ConstU8<18>;
+	type AdultOnlyTitles = AdultOnlyTitles;
+	type MinCommitDelay = ConstU32<3>;
+	type TransferOfferTimeout = ConstU32<{ 7 * DAYS }>;
+	type MaxBatch = ConstU32<64>;
+	type RejectAddressLikeNames = ConstBool<true>;
+	type RejectDuplicateProfiles = ConstBool<true>;
+	type AutoTitleFromAge = ConstBool<false>;
+	type MaxRedirects = ConstU32<4>;
+	type MaxRegistrationsPerBlock = ConstU32<100>;
+	type MaxClaims = ConstU32<2>;
+	type ClaimLen = ConstU32<128>;
+	type HeartbeatInterval = ConstU32<HOURS>;
+	type AdminWeightBudget = AdminWeightBudget;
+	type OnNameChange = ();
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -317,7 +341,9 @@ pub type SignedExtra = (
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	pallet_template::migrations::v1::MigrateToV1<Runtime>,
+);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -577,6 +603,101 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template::runtime_api::NicksApi<
+		Block,
+		AccountId,
+		pallet_template::User<Runtime>,
+		Hash,
+	> for Runtime {
+		fn config_values() -> pallet_template::NickConfig {
+			TemplateModule::config_values()
+		}
+
+		fn whoami(account: AccountId) -> Option<pallet_template::User<Runtime>> {
+			TemplateModule::whoami(&account)
+		}
+
+		fn whoami_or_default(account: AccountId) -> pallet_template::User<Runtime> {
+			TemplateModule::whoami_or_default(&account)
+		}
+
+		fn export_profile(account: AccountId, viewer: Option<AccountId>) -> Option<Vec<u8>> {
+			TemplateModule::export_profile(&account, viewer.as_ref())
+		}
+
+		fn whoami_as(account: AccountId, viewer: Option<AccountId>) -> Option<pallet_template::User<Runtime>> {
+			TemplateModule::whoami_as(&account, viewer.as_ref())
+		}
+
+		fn is_minor(account: AccountId) -> Option<bool> {
+			TemplateModule::is_minor(&account)
+		}
+
+		fn encoded_len_of(account: AccountId) -> Option<u32> {
+			TemplateModule::encoded_len_of(&account)
+		}
+
+		fn title_counts(limit: u32) -> Vec<(Vec<u8>, u32)> {
+			TemplateModule::title_counts(limit)
+		}
+
+		fn safe_truncate(bytes: Vec<u8>, max: u32) -> Vec<u8> {
+			TemplateModule::safe_truncate(&bytes, max as usize)
+		}
+
+		fn profile_digest(account: AccountId) -> Option<Hash> {
+			TemplateModule::profile_digest(&account)
+		}
+
+		fn users_batch(accounts: Vec<AccountId>) -> Vec<Option<pallet_template::User<Runtime>>> {
+			TemplateModule::users_batch(accounts)
+		}
+
+		fn names_with_prefix_paged(
+			prefix: Vec<u8>,
+			start: Option<Vec<u8>>,
+			limit: u32,
+		) -> (Vec<(Vec<u8>, AccountId)>, Option<Vec<u8>>) {
+			TemplateModule::names_with_prefix_paged(&prefix, start, limit)
+		}
+
+		fn length_histogram() -> Vec<(u32, u32)> {
+			TemplateModule::length_histogram()
+		}
+
+		fn status_of(account: AccountId) -> Option<pallet_template::ProfileStatus> {
+			TemplateModule::status_of(&account)
+		}
+
+		fn stats() -> pallet_template::NickStats {
+			TemplateModule::stats()
+		}
+
+		fn user_with_address(account: AccountId) -> Option<(Vec<u8>, u16)> {
+			TemplateModule::user_with_address(&account)
+		}
+
+		fn resolve(alias: Vec<u8>) -> Option<AccountId> {
+			TemplateModule::resolve(&alias)
+		}
+
+		fn display_alias(alias: Vec<u8>) -> Option<Vec<u8>> {
+			TemplateModule::display_alias(&alias)
+		}
+
+		fn can_register(account: AccountId, name_len: u32, title_len: u32) -> Result<(), u8> {
+			TemplateModule::can_register(&account, name_len, title_len)
+		}
+
+		fn runtime_config() -> pallet_template::RuntimeConfig {
+			TemplateModule::runtime_config()
+		}
+
+		fn is_active(account: AccountId, window: u32) -> bool {
+			TemplateModule::is_active(&account, window)
+		}
+	}
+
 	impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
 		fn create_default_config() -> Vec<u8> {
 			create_default_config::<RuntimeGenesisConfig>()